@@ -0,0 +1,379 @@
+//! Affine transforms, any combination of translations, rotations, scales, shears and reflections
+use std::fmt;
+use std::ops::Mul;
+
+use crate::{
+    boundingbox::BoundingBox, line::Line, multipolygon::MultiPolygon, point::Point,
+    polygon::Polygon, polygonwithholes::PolygonWithHoles, tolerance::Tolerance,
+};
+
+/// A 2d affine transform, the top two rows of a 3x3 matrix.
+///
+/// A point (x, y) is moved to (a * x + b * y + c, d * x + e * y + f). Build one from the constructors, combine them
+/// with `then` and apply the result to as many shapes as you like.
+///
+/// ```
+/// use polygonical::affine::Affine2;
+/// use polygonical::point::Point;
+///
+/// let transform = Affine2::scale(2.0, 2.0)
+///     .then(&Affine2::rotate(90.0_f64.to_radians()))
+///     .then(&Affine2::translate(Point::new(1.0, 0.0)));
+///
+/// assert_eq!(transform.apply(&Point::new(1.0, 0.0)), Point::new(1.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Affine2 {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Affine2 {
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Affine2 { a, b, c, d, e, f }
+    }
+
+    /// The transform that leaves everything where it is.
+    pub const fn identity() -> Self {
+        Affine2::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0)
+    }
+
+    /// Move everything by the given offset.
+    pub fn translate(by: Point) -> Self {
+        Affine2::new(1.0, 0.0, by.x, 0.0, 1.0, by.y)
+    }
+
+    /// Rotate counter clockwise around the origin by angle radians.
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Affine2::new(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+
+    /// Rotate counter clockwise around the anchor point by angle radians.
+    pub fn rotate_around(anchor: Point, angle: f64) -> Self {
+        Affine2::around(anchor, Affine2::rotate(angle))
+    }
+
+    /// Scale away from the origin, multiplying x by sx and y by sy.
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Affine2::new(sx, 0.0, 0.0, 0.0, sy, 0.0)
+    }
+
+    /// Scale away from the anchor point, multiplying distances along x by sx and along y by sy.
+    pub fn scale_around(anchor: Point, sx: f64, sy: f64) -> Self {
+        Affine2::around(anchor, Affine2::scale(sx, sy))
+    }
+
+    /// Shear along both axes, x moves by kx times y and y moves by ky times x.
+    pub fn shear(kx: f64, ky: f64) -> Self {
+        Affine2::new(1.0, kx, 0.0, ky, 1.0, 0.0)
+    }
+
+    /// Reflect across the line through the origin that is angle radians counter clockwise from the x axis.
+    pub fn reflect(angle: f64) -> Self {
+        let (sin, cos) = (2.0 * angle).sin_cos();
+        Affine2::new(cos, sin, 0.0, sin, -cos, 0.0)
+    }
+
+    /// Do transform centered on the anchor point rather than the origin.
+    fn around(anchor: Point, transform: Affine2) -> Self {
+        Affine2::translate(-anchor)
+            .then(&transform)
+            .then(&Affine2::translate(anchor))
+    }
+
+    /// Create a transform that does this one and then the next one.
+    pub fn then(&self, next: &Affine2) -> Affine2 {
+        *next * *self
+    }
+
+    /// The determinant of the matrix, how much areas are multiplied by.
+    /// This is negative if the transform mirrors things and zero if it squashes them flat.
+    pub fn determinant(&self) -> f64 {
+        self.a * self.e - self.b * self.d
+    }
+
+    /// Create the transform that undoes this one.
+    /// Returns None if this squashes everything onto a line or a point, as that can't be undone.
+    pub fn invert(&self) -> Option<Affine2> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let a = self.e / det;
+        let b = -self.b / det;
+        let d = -self.d / det;
+        let e = self.a / det;
+        Some(Affine2::new(
+            a,
+            b,
+            -(a * self.c + b * self.f),
+            d,
+            e,
+            -(d * self.c + e * self.f),
+        ))
+    }
+
+    /// Move a single point.
+    pub fn transform_point(&self, p: Point) -> Point {
+        Point::new(
+            self.a * p.x + self.b * p.y + self.c,
+            self.d * p.x + self.e * p.y + self.f,
+        )
+    }
+
+    /// Create a copy of the shape with this transform applied to it.
+    pub fn apply<S: Transformable>(&self, shape: &S) -> S {
+        shape.map_points(|p| self.transform_point(p))
+    }
+
+    /// Returns true if every part of the two matrices is equal within the tolerance.
+    pub fn approx_eq(&self, other: &Affine2, tolerance: Tolerance) -> bool {
+        [
+            (self.a, other.a),
+            (self.b, other.b),
+            (self.c, other.c),
+            (self.d, other.d),
+            (self.e, other.e),
+            (self.f, other.f),
+        ]
+        .iter()
+        .all(|(x, y)| tolerance.approx_eq(*x, *y))
+    }
+}
+
+impl Default for Affine2 {
+    fn default() -> Self {
+        Affine2::identity()
+    }
+}
+
+/// Matrix multiplication, `x * y` does y first and then x.
+impl Mul for Affine2 {
+    type Output = Affine2;
+
+    fn mul(self, other: Affine2) -> Affine2 {
+        Affine2::new(
+            self.a * other.a + self.b * other.d,
+            self.a * other.b + self.b * other.e,
+            self.a * other.c + self.b * other.f + self.c,
+            self.d * other.a + self.e * other.d,
+            self.d * other.b + self.e * other.e,
+            self.d * other.c + self.e * other.f + self.f,
+        )
+    }
+}
+
+impl PartialEq for Affine2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, Tolerance::default())
+    }
+}
+
+impl fmt::Display for Affine2 {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Affine2([{}, {}, {}], [{}, {}, {}])",
+            self.a, self.b, self.c, self.d, self.e, self.f
+        )
+    }
+}
+
+/// Shapes that can be transformed by moving each of their points.
+pub trait Transformable: Sized {
+    /// Create a copy of this shape with every point moved by f.
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self;
+}
+
+impl Transformable for Point {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        f(*self)
+    }
+}
+
+impl Transformable for Line {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        Line::new(f(self.a), f(self.b))
+    }
+}
+
+/// The result is the smallest box that holds all four corners once they have been moved.
+impl Transformable for BoundingBox {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        BoundingBox::from_points(&self.to_polygon().points.into_iter().map(f).collect())
+    }
+}
+
+/// If the transform mirrors the polygon the points are reversed, so it keeps going the same way around.
+impl Transformable for Polygon {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        let result = Polygon::new(self.points.iter().map(|p| f(*p)).collect());
+
+        if result.signed_area() * self.signed_area() < 0.0 {
+            result.reversed()
+        } else {
+            result
+        }
+    }
+}
+
+impl Transformable for PolygonWithHoles {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        PolygonWithHoles::new(
+            self.exterior.map_points(&f),
+            self.interiors.iter().map(|h| h.map_points(&f)).collect(),
+        )
+    }
+}
+
+impl Transformable for MultiPolygon {
+    fn map_points<F: Fn(Point) -> Point>(&self, f: F) -> Self {
+        self.polygons.iter().map(|p| p.map_points(&f)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Affine2;
+    use crate::{
+        boundingbox::BoundingBox,
+        line::Line,
+        point::Point,
+        polygonwithholes::PolygonWithHoles,
+        tests::{assert_f64, square},
+    };
+
+    macro_rules! point_tests {
+        ($($name:ident: $transform:expr, $point:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($transform.apply(&$point), $expected);
+                }
+            )*
+        };
+    }
+
+    point_tests!(
+        identity: Affine2::identity(), Point::new(3.0, 4.0), Point::new(3.0, 4.0),
+        translate: Affine2::translate(Point::new(1.0, -2.0)), Point::new(3.0, 4.0), Point::new(4.0, 2.0),
+        rotate: Affine2::rotate(90.0_f64.to_radians()), Point::new(1.0, 0.0), Point::new(0.0, 1.0),
+        rotate_around: Affine2::rotate_around(Point::new(1.0, 1.0), 180.0_f64.to_radians()),
+            Point::new(2.0, 1.0), Point::new(0.0, 1.0),
+        scale: Affine2::scale(2.0, -3.0), Point::new(1.0, 1.0), Point::new(2.0, -3.0),
+        scale_around: Affine2::scale_around(Point::new(1.0, 1.0), 2.0, 2.0), Point::new(2.0, 3.0), Point::new(3.0, 5.0),
+        shear: Affine2::shear(2.0, 0.0), Point::new(1.0, 3.0), Point::new(7.0, 3.0),
+        reflect_x_axis: Affine2::reflect(0.0), Point::new(2.0, 3.0), Point::new(2.0, -3.0),
+        reflect_diagonal: Affine2::reflect(45.0_f64.to_radians()), Point::new(2.0, 3.0), Point::new(3.0, 2.0),
+    );
+
+    #[test]
+    fn matches_point_methods() {
+        let p = Point::new(2.5, -1.5);
+        let angle = 0.7;
+
+        assert_eq!(Affine2::rotate(angle).apply(&p), p.rotate(angle));
+        assert_eq!(Affine2::scale(3.0, 0.5).apply(&p), p.scale(3.0, 0.5));
+    }
+
+    #[test]
+    fn composition_order() {
+        let rotate = Affine2::rotate(90.0_f64.to_radians());
+        let translate = Affine2::translate(Point::new(1.0, 0.0));
+        let p = Point::new(1.0, 0.0);
+
+        assert_eq!(rotate.then(&translate).apply(&p), Point::new(1.0, 1.0));
+        assert_eq!(translate.then(&rotate).apply(&p), Point::new(0.0, 2.0));
+        assert_eq!(translate * rotate, rotate.then(&translate));
+    }
+
+    #[test]
+    fn invert() {
+        let transform = Affine2::scale(2.0, 0.5)
+            .then(&Affine2::shear(0.3, -0.2))
+            .then(&Affine2::rotate(1.2))
+            .then(&Affine2::translate(Point::new(5.0, -7.0)));
+        let inverse = transform.invert().unwrap();
+
+        assert_eq!(transform.then(&inverse), Affine2::identity());
+        assert_eq!(inverse.then(&transform), Affine2::identity());
+
+        let p = Point::new(-3.0, 8.0);
+        assert_eq!(inverse.apply(&transform.apply(&p)), p);
+    }
+
+    #[test]
+    fn invert_flat() {
+        assert!(Affine2::scale(1.0, 0.0).invert().is_none());
+        assert!(Affine2::shear(1.0, 1.0).invert().is_none());
+    }
+
+    #[test]
+    fn determinant() {
+        assert_f64!(Affine2::rotate(0.4).determinant(), 1.0);
+        assert_f64!(Affine2::scale(2.0, 3.0).determinant(), 6.0);
+        assert_f64!(Affine2::reflect(0.4).determinant(), -1.0);
+    }
+
+    #[test]
+    fn line() {
+        let line = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+        let result = Affine2::translate(Point::new(1.0, 2.0)).apply(&line);
+
+        assert_eq!(result.a, Point::new(1.0, 2.0));
+        assert_eq!(result.b, Point::new(2.0, 3.0));
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bbox = BoundingBox::new(Point::new(0.0, 0.0), Point::new(2.0, 1.0));
+
+        let result = Affine2::rotate(90.0_f64.to_radians()).apply(&bbox);
+        assert_eq!(result.a, Point::new(-1.0, 0.0));
+        assert_eq!(result.b, Point::new(0.0, 2.0));
+
+        let result = Affine2::rotate(45.0_f64.to_radians()).apply(&bbox);
+        assert_f64!(result.width(), 3.0 / 2.0_f64.sqrt());
+        assert_f64!(result.height(), 3.0 / 2.0_f64.sqrt());
+    }
+
+    #[test]
+    fn polygon() {
+        let poly = square(0.0, 0.0, 1.0);
+        let transform = Affine2::rotate_around(poly.center(), 0.5)
+            .then(&Affine2::translate(Point::new(3.0, 1.0)));
+
+        let expected = poly
+            .rotate_around_center(0.5)
+            .translate(Point::new(3.0, 1.0));
+        assert_eq!(transform.apply(&poly), expected);
+    }
+
+    #[test]
+    fn polygon_mirrored() {
+        let poly = square(1.0, 0.0, 1.0);
+        let result = Affine2::reflect(90.0_f64.to_radians()).apply(&poly);
+
+        assert_eq!(result, poly.scale_around_origin(-1.0, 1.0));
+        assert_eq!(result.orientation(), poly.orientation());
+        assert_f64!(result.area(), 1.0);
+    }
+
+    #[test]
+    fn polygon_with_holes() {
+        let donut = PolygonWithHoles::new(square(0.0, 0.0, 3.0), vec![square(1.0, 1.0, 1.0)]);
+        let result = Affine2::scale(-2.0, 1.0).apply(&donut);
+
+        assert_f64!(result.area(), 16.0);
+        assert!(!result.contains(Point::new(-3.0, 1.5)));
+        assert!(result.contains(Point::new(-1.0, 1.5)));
+        assert_eq!(result.exterior.orientation(), donut.exterior.orientation());
+    }
+}
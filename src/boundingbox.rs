@@ -1,21 +1,21 @@
 use std::fmt;
 
-use crate::{point::Point, polygon::Polygon};
+use crate::{point::Point, polygon::Polygon, scalar::Scalar};
 
 #[derive(Debug, Clone, Copy)]
-pub struct BoundingBox {
-    pub a: Point,
-    pub b: Point,
+pub struct BoundingBox<T = f64> {
+    pub a: Point<T>,
+    pub b: Point<T>,
 }
 
-impl BoundingBox {
-    pub fn new(a: Point, b: Point) -> Self {
+impl<T: Scalar> BoundingBox<T> {
+    pub fn new(a: Point<T>, b: Point<T>) -> Self {
         BoundingBox { a, b }
     }
 
-    pub fn from_points(points: &Vec<Point>) -> Self {
-        let mut min = Point::new_max();
-        let mut max = Point::new_min();
+    pub fn from_points(points: &Vec<Point<T>>) -> Self {
+        let mut min = Point::from_xy(T::MAX, T::MAX);
+        let mut max = Point::from_xy(T::MIN, T::MIN);
 
         for p in points {
             min = p.min(&min);
@@ -25,29 +25,29 @@ impl BoundingBox {
         BoundingBox { a: min, b: max }
     }
 
-    pub fn contains(&self, p: Point) -> bool {
+    pub fn contains(&self, p: Point<T>) -> bool {
         self.a.x <= p.x && self.b.x >= p.x && self.a.y <= p.y && self.b.y >= p.y
     }
 
-    pub fn to_polygon(&self) -> Polygon {
+    pub fn to_polygon(&self) -> Polygon<T> {
         let points = vec![
-            Point::new(self.a.x, self.a.y),
-            Point::new(self.a.x, self.b.y),
-            Point::new(self.b.x, self.b.y),
-            Point::new(self.b.x, self.a.y),
+            Point::from_xy(self.a.x, self.a.y),
+            Point::from_xy(self.a.x, self.b.y),
+            Point::from_xy(self.b.x, self.b.y),
+            Point::from_xy(self.b.x, self.a.y),
         ];
         Polygon::new(points)
     }
 
-    pub fn intersects(&self, other: &BoundingBox) -> bool {
-        other.contains(Point::new(self.a.x, self.a.y))
-            || other.contains(Point::new(self.b.x, self.a.y))
-            || other.contains(Point::new(self.a.x, self.b.y))
-            || other.contains(Point::new(self.b.x, self.b.y))
-            || self.contains(Point::new(other.a.x, other.a.y))
-            || self.contains(Point::new(other.b.x, other.a.y))
-            || self.contains(Point::new(other.a.x, other.b.y))
-            || self.contains(Point::new(other.b.x, other.b.y))
+    pub fn intersects(&self, other: &BoundingBox<T>) -> bool {
+        other.contains(Point::from_xy(self.a.x, self.a.y))
+            || other.contains(Point::from_xy(self.b.x, self.a.y))
+            || other.contains(Point::from_xy(self.a.x, self.b.y))
+            || other.contains(Point::from_xy(self.b.x, self.b.y))
+            || self.contains(Point::from_xy(other.a.x, other.a.y))
+            || self.contains(Point::from_xy(other.b.x, other.a.y))
+            || self.contains(Point::from_xy(other.a.x, other.b.y))
+            || self.contains(Point::from_xy(other.b.x, other.b.y))
             || (other.a.x >= self.a.x
                 && other.b.x <= self.b.x
                 && self.a.y >= other.a.y
@@ -58,16 +58,16 @@ impl BoundingBox {
                 && self.b.x <= other.b.x)
     }
 
-    pub fn width(&self) -> f64 {
+    pub fn width(&self) -> T {
         self.b.x - self.a.x
     }
 
-    pub fn height(&self) -> f64 {
+    pub fn height(&self) -> T {
         self.b.y - self.a.y
     }
 }
 
-impl fmt::Display for BoundingBox {
+impl<T: fmt::Display> fmt::Display for BoundingBox<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "BoundingBox({}, {})", self.a, self.b)
     }
@@ -120,4 +120,19 @@ mod tests {
         assert_f64!(bbox.width(), 2.0);
         assert_f64!(bbox.height(), 4.4);
     }
+
+    #[test]
+    fn integer_coordinates() {
+        let points: Vec<Point<i32>> = vec![
+            Point::from_xy(2, -1),
+            Point::from_xy(-3, 4),
+            Point::from_xy(1, 1),
+        ];
+        let bbox = BoundingBox::from_points(&points);
+
+        assert_eq!(bbox.a, Point::from_xy(-3, -1));
+        assert_eq!(bbox.width(), 5);
+        assert_eq!(bbox.height(), 5);
+        assert!(bbox.contains(Point::from_xy(0, 0)));
+    }
 }
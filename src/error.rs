@@ -0,0 +1,60 @@
+//! Errors returned by the fallible functions in this library
+use std::fmt;
+
+use crate::{point::Point, scalar::Scalar};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A polygon needs at least 3 points, this holds how many it was given.
+    TooFewPoints(usize),
+    /// A point had a NaN or infinite coordinate.
+    NonFiniteCoordinate(Point),
+    /// The polygon crosses over itself, so the operation has no sensible answer.
+    SelfIntersecting,
+    /// A point is too far from the origin to be converted to integer coordinates, this holds the scaled point.
+    CoordinateOutOfRange(Point),
+    /// A boolean operation couldn't join the edges of its result back up into closed rings that don't touch or cross
+    /// themselves.
+    OverlayFailed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooFewPoints(n) => write!(
+                formatter,
+                "Trying to create a polygon with {} points. You need at least 3",
+                n
+            ),
+            Error::NonFiniteCoordinate(p) => {
+                write!(formatter, "Point {} does not have finite coordinates", p)
+            }
+            Error::SelfIntersecting => write!(formatter, "The polygon is self intersecting"),
+            Error::CoordinateOutOfRange(p) => write!(
+                formatter,
+                "Point {} is too big for integer coordinates, they must be within 2^60 of the origin",
+                p
+            ),
+            Error::OverlayFailed => write!(
+                formatter,
+                "The boolean operation could not join the edges of the result into simple closed rings"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Check that all the points have usable coordinates
+pub(crate) fn check_finite<'a, T, I>(points: I) -> Result<(), Error>
+where
+    T: Scalar + 'a,
+    I: IntoIterator<Item = &'a Point<T>>,
+{
+    for p in points {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            return Err(Error::NonFiniteCoordinate(p.cast()));
+        }
+    }
+    Ok(())
+}
@@ -1,9 +1,24 @@
 /// Geometry helper functions for various things. None of this is exposed outside the library
-use crate::point::Point;
+use crate::{
+    point::Point,
+    scalar::{self, Scalar},
+};
 
-// calculate the area of a triangle
-pub fn area_of_triangle(a: Point, b: Point, c: Point) -> f64 {
-    0.5 * matrix_determinant(b.y - a.x, b.x - a.x, c.y - a.y, c.x - a.y)
+/// Calculate the signed area of a triangle. Positive if a -> b -> c goes counter clockwise and negative if clockwise.
+pub fn signed_area_of_triangle(a: Point, b: Point, c: Point) -> f64 {
+    0.5 * matrix_determinant(b.x - a.x, b.y - a.y, c.x - a.x, c.y - a.y)
+}
+
+/// Calculate the signed area of a ring of points. Positive for counter clockwise rings and negative for clockwise ones.
+///
+/// This adds up a fan of triangles from the first point rather than from the origin,
+/// so polygons a long way from the origin don't lose precision.
+pub fn signed_area(points: &[Point]) -> f64 {
+    let mut total = 0.0;
+    for i in 1..points.len().saturating_sub(1) {
+        total += signed_area_of_triangle(points[0], points[i], points[i + 1]);
+    }
+    total
 }
 
 /// Find the point of intersection of two lines a->b and c->d
@@ -21,9 +36,7 @@ pub fn point_of_intersection(a: Point, b: Point, c: Point, d: Point) -> Option<P
 
     if determinant == 0.0 {
         // they might be parallel but overlap so check if the start of the second line is in side the first.
-        let x_r = (b.x - c.x) / (b.x - a.x);
-        let y_r = (b.y - c.y) / (b.y - a.y);
-        if x_r > 0.0 && x_r < 1.0 && y_r > 0.0 && y_r < 1.0 {
+        if strictly_on_segment(a, b, c) {
             return Some(c);
         }
         return None;
@@ -32,43 +45,123 @@ pub fn point_of_intersection(a: Point, b: Point, c: Point, d: Point) -> Option<P
     let x = (b2 * c1 - b1 * c2) / determinant;
     let y = (a1 * c2 - a2 * c1) / determinant;
 
-    // check that x,y is inside the two points a->b and c->d
-    let x_r1 = (b.x - x) / (b.x - a.x);
-    let y_r1 = (b.y - y) / (b.y - a.y);
-
-    let x_r2 = (d.x - x) / (d.x - c.x);
-    let y_r2 = (d.y - y) / (d.y - c.y);
+    // check that x,y is inside the two points a->b and c->d, by working out how far along each line it is.
+    // This avoids dividing by the width or height of the lines which can be zero.
+    let denominator = matrix_determinant(b.x - a.x, b.y - a.y, d.x - c.x, d.y - c.y);
+    let t = matrix_determinant(c.x - a.x, c.y - a.y, d.x - c.x, d.y - c.y) / denominator;
+    let u = matrix_determinant(c.x - a.x, c.y - a.y, b.x - a.x, b.y - a.y) / denominator;
 
     // The clippy suggestion here is less obvious to me
     #[allow(clippy::manual_range_contains)]
-    if x_r1 < 0.0
-        || x_r1 > 1.0
-        || y_r1 < 0.0
-        || y_r1 > 1.0
-        || x_r2 < 0.0
-        || x_r2 > 1.0
-        || y_r2 < 0.0
-        || y_r2 > 1.0
-    {
+    if t < 0.0 || t > 1.0 || u < 0.0 || u > 1.0 {
         return None;
     }
 
     Some(Point::new(x, y))
 }
 
-pub fn line_intersects_others(a: (Point, Point), others: &[(Point, Point)]) -> Option<usize> {
-    for (i, el) in others.iter().enumerate() {
-        if lines_intersect(a.0, a.1, el.0, el.1) {
-            return Some(i);
+/// Find a point where the line a->b touches the line c->d without crossing it,
+/// such as the end of one line sitting on the other or two lines overlapping.
+pub fn touching_point(a: Point, b: Point, c: Point, d: Point) -> Option<Point> {
+    [(c, a, b), (d, a, b), (a, c, d), (b, c, d)]
+        .into_iter()
+        .find(|(p, start, end)| {
+            orientation(*start, *end, *p) == Orientation::Collinear && on_segment(*start, *p, *end)
+        })
+        .map(|(p, _, _)| p)
+}
+
+/// If the line a->b and the line c->d share an end, return it followed by the other ends of a->b and c->d.
+pub fn shared_end<T: Scalar>(
+    a: Point<T>,
+    b: Point<T>,
+    c: Point<T>,
+    d: Point<T>,
+) -> Option<(Point<T>, Point<T>, Point<T>)> {
+    if a == c {
+        Some((a, b, d))
+    } else if a == d {
+        Some((a, b, c))
+    } else if b == c {
+        Some((b, a, d))
+    } else if b == d {
+        Some((b, a, c))
+    } else {
+        None
+    }
+}
+
+/// Find where two lines that both start at s, going to p and q, fold back along each other like the sides of a spike.
+/// Returns the far end of the part they overlap along, or None if they only meet at s.
+pub fn fold_point<T: Scalar>(s: Point<T>, p: Point<T>, q: Point<T>) -> Option<Point<T>> {
+    let same = |a: Point<T>, b: Point<T>| a.x == b.x && a.y == b.y;
+    if strictly_on_segment(s, p, q) || (same(p, q) && !same(p, s)) {
+        Some(q)
+    } else if strictly_on_segment(s, q, p) {
+        Some(p)
+    } else {
+        None
+    }
+}
+
+/// Calculate the winding number of a ring of points around p.
+///
+/// Counter clockwise loops count +1 and clockwise loops -1. Only uses multiplication and comparisons.
+/// Returns None if p is on one of the edges of the ring as the winding number isn't defined there.
+pub fn winding_number<T: Scalar>(points: &[Point<T>], p: Point<T>) -> Option<i32> {
+    let mut winding = 0;
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        let side = cross(a, b, p);
+
+        if side == 0.0 && on_segment(a, p, b) {
+            return None;
+        }
+
+        if a.y <= p.y {
+            // an upward crossing with p on the left
+            if b.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && side < 0.0 {
+            // a downward crossing with p on the right
+            winding -= 1;
         }
     }
 
-    None
+    Some(winding)
+}
+
+/// The cross product of a->b and a->c. Positive if c is to the left of the line a->b, negative if it is to the right.
+/// The sign is always exact, even when the points are very nearly in a line.
+pub fn cross<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>) -> f64 {
+    T::orient2d((a.x, a.y), (b.x, b.y), (c.x, c.y))
+}
+
+/// Find the point where the line a->b crosses the line c->d.
+/// Only meaningful when the two lines are known to properly cross, parallel lines will give nonsense.
+pub fn crossing_point(a: Point, b: Point, c: Point, d: Point) -> Point {
+    let t = matrix_determinant(c.x - a.x, c.y - a.y, d.x - c.x, d.y - c.y)
+        / matrix_determinant(b.x - a.x, b.y - a.y, d.x - c.x, d.y - c.y);
+
+    Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y))
+}
+
+/// Return true if the point p lies on the line a->b but is not one of its end points.
+pub fn strictly_on_segment<T: Scalar>(a: Point<T>, b: Point<T>, p: Point<T>) -> bool {
+    if orientation(a, b, p) != Orientation::Collinear {
+        return false;
+    }
+    if (p.x == a.x && p.y == a.y) || (p.x == b.x && p.y == b.y) {
+        return false;
+    }
+    on_segment(a, p, b)
 }
 
 /// lines_intersect returns true if the line between a and b intersects with a line between c and d.
 /// Note: if the lines intersect past the two points false will be returned.
-pub fn lines_intersect(a: Point, b: Point, c: Point, d: Point) -> bool {
+pub fn lines_intersect<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>, d: Point<T>) -> bool {
     let o1 = orientation(a, b, c);
     let o2 = orientation(a, b, d);
 
@@ -95,26 +188,30 @@ pub fn lines_intersect(a: Point, b: Point, c: Point, d: Point) -> bool {
     false
 }
 
-#[derive(PartialEq)]
-enum Orientation {
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Orientation {
     Collinear,
     Clockwise,
     AntiClockwise,
 }
 
-fn orientation(a: Point, b: Point, c: Point) -> Orientation {
-    let v: f64 = (b.y - a.y) * (c.x - b.x) - (b.x - a.x) * (c.y - b.y);
+/// Which way do we turn going from a to b to c
+pub fn orientation<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>) -> Orientation {
+    let v = cross(a, b, c);
 
     if v == 0.0 {
         return Orientation::Collinear;
-    } else if v > 0.0 {
+    } else if v < 0.0 {
         return Orientation::Clockwise;
     }
     Orientation::AntiClockwise
 }
 
-fn on_segment(a: Point, b: Point, c: Point) -> bool {
-    b.x <= a.x.max(c.x) && b.x >= a.x.min(c.x) && b.y <= a.y.max(c.y) && b.y >= a.y.min(c.y)
+fn on_segment<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>) -> bool {
+    b.x <= scalar::max(a.x, c.x)
+        && b.x >= scalar::min(a.x, c.x)
+        && b.y <= scalar::max(a.y, c.y)
+        && b.y >= scalar::min(a.y, c.y)
 }
 
 fn matrix_determinant(a: f64, b: f64, c: f64, d: f64) -> f64 {
@@ -123,9 +220,12 @@ fn matrix_determinant(a: f64, b: f64, c: f64, d: f64) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::area_of_triangle;
     use super::lines_intersect;
+    use super::orientation;
     use super::point_of_intersection;
+    use super::signed_area;
+    use super::signed_area_of_triangle;
+    use super::Orientation;
     use crate::point::Point;
     use crate::tests::assert_f64;
 
@@ -147,12 +247,35 @@ mod tests {
         does_intersect_but_not: (Point::new(1.0, 0.0), Point::new(1.0, 2.0)), (Point::new(0.0, 3.0), Point::new(2.0, 3.0)), false,
     );
 
+    // the gap between floats just above 0.5
+    const ULP: f64 = f64::EPSILON / 2.0;
+
+    macro_rules! orientation_tests {
+        ($($name:ident: $points:expr,$expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (a, b, c) = $points;
+                    assert_eq!(orientation(a, b, c), $expected);
+                }
+            )*
+        };
+    }
+
+    orientation_tests!(
+        turn_left: (Point::zero(), Point::new(1.0, 0.0), Point::new(1.0, 1.0)), Orientation::AntiClockwise,
+        turn_right: (Point::zero(), Point::new(1.0, 0.0), Point::new(1.0, -1.0)), Orientation::Clockwise,
+        straight_on: (Point::new(0.5, 0.5), Point::new(12.0, 12.0), Point::new(24.0, 24.0)), Orientation::Collinear,
+        nearly_straight_left: (Point::new(0.5, 0.5 + ULP), Point::new(12.0, 12.0), Point::new(24.0, 24.0)), Orientation::AntiClockwise,
+        nearly_straight_right: (Point::new(0.5 + ULP, 0.5), Point::new(12.0, 12.0), Point::new(24.0, 24.0)), Orientation::Clockwise,
+    );
+
     macro_rules! triangle_area_test {
         ($($name:ident: $value:expr,$expected:expr,)*) => {
             $(
                 #[test]
                 fn $name() {
-                    let result = area_of_triangle($value.0, $value.1, $value.2);
+                    let result = signed_area_of_triangle($value.0, $value.1, $value.2);
                     assert_f64!(result, $expected);
                 }
             )*
@@ -162,14 +285,27 @@ mod tests {
     triangle_area_test!(
         ta_test1: (Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(0.0, 0.0)), 0.0,
         ta_test2: (Point::new(0.0, 0.0), Point::new(0.0, 0.0), Point::new(0.0, 1.0)), 0.0,
-        ta_test3: (Point::new(0.0, 1.0), Point::new(3.0, 6.0), Point::new(6.0, 2.0)), 13.5,
+        ta_test3: (Point::new(0.0, 1.0), Point::new(3.0, 6.0), Point::new(6.0, 2.0)), -13.5,
 
         ta_test4: (Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(0.0, 0.0)), 0.0,
         ta_test5: (Point::new(0.0, 0.0), Point::new(0.0, 0.0), Point::new(0.0, 1.0)), 0.0,
-        ta_test6: (Point::new(0.0, 0.0), Point::new(0.0, 1.0), Point::new(1.0, 1.0)), 0.5,
-        ta_test7: (Point::new(0.0, 0.0), Point::new(1.0, 1.0), Point::new(1.0, 0.0)), 0.5,
+        ta_test6: (Point::new(0.0, 0.0), Point::new(0.0, 1.0), Point::new(1.0, 1.0)), -0.5,
+        ta_test7: (Point::new(0.0, 0.0), Point::new(1.0, 1.0), Point::new(1.0, 0.0)), -0.5,
+        ta_test8: (Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(1.0, 1.0)), 0.5,
+        ta_test9: (Point::new(2.0, 3.0), Point::new(4.0, 3.0), Point::new(2.0, 7.0)), 4.0,
     );
 
+    #[test]
+    fn signed_area_far_from_origin() {
+        let points = vec![
+            Point::new(1e9, 1e9),
+            Point::new(1e9 + 1.0, 1e9),
+            Point::new(1e9 + 1.0, 1e9 + 1.0),
+            Point::new(1e9, 1e9 + 1.0),
+        ];
+        assert_f64!(signed_area(&points), 1.0);
+    }
+
     macro_rules! intersection_point_test {
         ($($name:ident: $line_a:expr,$line_b:expr,$expected:expr,)*) => {
             $(
@@ -186,6 +322,12 @@ mod tests {
         not_intersecting: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)), (Point::new(1.0, 0.0), Point::new(2.0, 1.0)), None,
         simple_90: (Point::new(1.0, 0.0), Point::new(1.0, 2.0)), (Point::new(0.0, 1.0), Point::new(2.0, 1.0)), Some(Point::new(1.0, 1.0)),
         intersects_outside: (Point::new(1.0, 0.0), Point::new(1.0, 2.0)), (Point::new(0.0, 3.0), Point::new(2.0, 3.0)), None,
+        vertical_horizontal: (Point::new(0.1, 0.0), Point::new(0.1, 0.7)), (Point::new(0.0, 0.3), Point::new(0.7, 0.3)), Some(Point::new(0.1, 0.3)),
+        steep_cross: (Point::new(0.1, 0.0), Point::new(0.1000001, 3.0)), (Point::new(0.0, 1.0), Point::new(3.0, 1.0)), Some(Point::new(0.1, 1.0)),
         intersection_parallel: (Point::new(0.0, 0.0), Point::new(2.0, 2.0)), (Point::new(1.0, 1.0), Point::new(3.0, 3.0)), Some(Point::new(1.0, 1.0)),
+        intersection_parallel_horizontal: (Point::new(0.0, 0.0), Point::new(2.0, 0.0)), (Point::new(1.0, 0.0), Point::new(3.0, 0.0)), Some(Point::new(1.0, 0.0)),
+        intersection_parallel_vertical: (Point::new(1.0, 0.0), Point::new(1.0, 2.0)), (Point::new(1.0, 1.0), Point::new(1.0, 3.0)), Some(Point::new(1.0, 1.0)),
+        parallel_apart_horizontal: (Point::new(0.0, 0.0), Point::new(2.0, 0.0)), (Point::new(1.0, 1.0), Point::new(3.0, 1.0)), None,
+        parallel_not_in_line: (Point::new(0.0, 0.0), Point::new(2.0, 2.0)), (Point::new(1.0, 1.5), Point::new(3.0, 3.5)), None,
     );
 }
@@ -1,15 +1,28 @@
 #![doc = include_str!("../README.md")]
 
+pub mod affine;
 pub mod boundingbox;
 pub mod line;
+pub mod multipolygon;
 pub mod point;
 pub mod polygon;
+pub mod polygonwithholes;
+pub mod predicates;
+pub mod projective;
+pub mod scalar;
+pub mod tolerance;
 
+pub use error::Error;
+
+mod error;
 mod geom;
 mod maths;
+mod overlay;
+mod sweep;
 
 #[cfg(test)]
 mod tests {
+    use crate::{point::Point, polygon::Polygon};
 
     macro_rules! assert_f64 {
         ($actual:expr, $expected:expr) => {
@@ -20,9 +33,27 @@ mod tests {
                 $expected
             );
         };
+        ($actual:expr, $expected:expr, epsilon = $epsilon:expr) => {
+            assert!(
+                float_cmp::approx_eq!(f64, $actual, $expected, epsilon = $epsilon, ulps = 2),
+                "got:{} expected:{}",
+                $actual,
+                $expected
+            );
+        };
     }
     pub(crate) use assert_f64;
 
+    /// A clockwise square with its bottom left corner at x, y.
+    pub(crate) fn square(x: f64, y: f64, size: f64) -> Polygon {
+        Polygon::new(vec![
+            Point::new(x, y),
+            Point::new(x, y + size),
+            Point::new(x + size, y + size),
+            Point::new(x + size, y),
+        ])
+    }
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
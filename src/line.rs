@@ -1,15 +1,42 @@
 use std::fmt;
 
-use crate::{geom, point::Point};
+use crate::{
+    geom::{self, Orientation},
+    point::Point,
+    scalar::Scalar,
+    tolerance::Tolerance,
+};
+
+/// Which side of a line a point is on, looking along the line from a to b.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+    /// The point is exactly in line with the line, it may be past either end of it.
+    On,
+}
+
+/// Where two lines meet, see [`Line::intersection`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentIntersection {
+    /// The lines don't touch.
+    None,
+    /// The lines meet at a single point. This also holds how far along each line the point is,
+    /// from 0 at its start to 1 at its end.
+    Point(Point, f64, f64),
+    /// The lines are in a line with each other and share this part, which goes the same way as the first line.
+    Overlap(Line),
+}
 
 /// Represents a line between two points.
-pub struct Line {
-    pub a: Point,
-    pub b: Point,
+#[derive(Debug, Clone, Copy)]
+pub struct Line<T = f64> {
+    pub a: Point<T>,
+    pub b: Point<T>,
 }
 
-impl Line {
-    pub fn new(a: Point, b: Point) -> Self {
+impl<T: Scalar> Line<T> {
+    pub fn new(a: Point<T>, b: Point<T>) -> Self {
         Line { a, b }
     }
 
@@ -17,13 +44,273 @@ impl Line {
         geom::lines_intersect(self.a, self.b, other.a, other.b)
     }
 
+    /// Create a copy of this line going the other way, from b to a.
+    pub fn reversed(&self) -> Line<T> {
+        Line::new(self.b, self.a)
+    }
+
+    /// Work out which side of this line the point is on. This is exact, even for points very close to the line.
+    pub fn side_of(&self, p: Point<T>) -> Side {
+        match geom::orientation(self.a, self.b, p) {
+            Orientation::AntiClockwise => Side::Left,
+            Orientation::Clockwise => Side::Right,
+            Orientation::Collinear => Side::On,
+        }
+    }
+}
+
+impl Line {
     pub fn point_of_intersection(&self, other: &Self) -> Option<Point> {
         geom::point_of_intersection(self.a, self.b, other.a, other.b)
     }
+
+    /// Work out where this line meets the other one.
+    ///
+    /// Whether they touch at all is worked out exactly, the same as `intersects_with`. Where the lines touch at the end
+    /// of one of them or overlap the points returned are the exact end points, only a crossing point is calculated.
+    pub fn intersection(&self, other: &Line) -> SegmentIntersection {
+        let (a, b, c, d) = (self.a, self.b, other.a, other.b);
+        if !geom::lines_intersect(a, b, c, d) {
+            return SegmentIntersection::None;
+        }
+
+        let c_on_self = geom::orientation(a, b, c) == Orientation::Collinear;
+        let d_on_self = geom::orientation(a, b, d) == Orientation::Collinear;
+        if c_on_self && d_on_self {
+            return self.overlap(other);
+        }
+
+        let point = |p: Point, t: f64, u: f64| SegmentIntersection::Point(p, t, u);
+        if c_on_self {
+            return point(c, self.param(c), 0.0);
+        }
+        if d_on_self {
+            return point(d, self.param(d), 1.0);
+        }
+        if geom::orientation(c, d, a) == Orientation::Collinear {
+            return point(a, 0.0, other.param(a));
+        }
+        if geom::orientation(c, d, b) == Orientation::Collinear {
+            return point(b, 1.0, other.param(b));
+        }
+
+        // the lines properly cross
+        let (r, s) = (b - a, d - c);
+        let denominator = r.cross(&s);
+        let t = ((c - a).cross(&s) / denominator).clamp(0.0, 1.0);
+        let u = ((c - a).cross(&r) / denominator).clamp(0.0, 1.0);
+        point(geom::crossing_point(a, b, c, d), t, u)
+    }
+
+    /// The part shared by two lines that are in a line with each other and known to touch.
+    fn overlap(&self, other: &Line) -> SegmentIntersection {
+        if self.a.approx_eq(&self.b, Tolerance::exact()) {
+            return SegmentIntersection::Point(self.a, 0.0, other.param(self.a));
+        }
+
+        let (mut first, mut last) = (other.a, other.b);
+        if self.param(first) > self.param(last) {
+            (first, last) = (last, first);
+        }
+        let start = if self.param(first) > 0.0 {
+            first
+        } else {
+            self.a
+        };
+        let end = if self.param(last) < 1.0 { last } else { self.b };
+
+        let (t_start, t_end) = (self.param(start), self.param(end));
+        if t_start >= t_end {
+            return SegmentIntersection::Point(start, t_start, other.param(start));
+        }
+        SegmentIntersection::Overlap(Line::new(start, end))
+    }
+
+    /// How far along this line the point p is when projected onto it, 0 at a and 1 at b.
+    /// Returns 0 if the line has no length.
+    fn param(&self, p: Point) -> f64 {
+        let along = self.b - self.a;
+        let length_squared = along.dot(&along);
+        if length_squared == 0.0 {
+            return 0.0;
+        }
+        (p - self.a).dot(&along) / length_squared
+    }
+
+    pub fn length(&self) -> f64 {
+        self.a.distance_to(&self.b)
+    }
+
+    pub fn midpoint(&self) -> Point {
+        self.point_at(0.5)
+    }
+
+    /// The point t of the way along the line, a when t is 0 and b when t is 1.
+    /// Values outside 0 to 1 give points past the ends of the line.
+    pub fn point_at(&self, t: f64) -> Point {
+        self.a.lerp(&self.b, t)
+    }
+
+    /// A vector of length 1 pointing from a to b. This is the origin if a and b are the same point.
+    pub fn direction(&self) -> Point {
+        (self.b - self.a).normalize()
+    }
+
+    /// A vector of length 1 at right angles to the line, pointing to its left.
+    pub fn normal(&self) -> Point {
+        self.direction().perpendicular()
+    }
+
+    /// Return the point on this line that is closest to p.
+    pub fn closest_point(&self, p: Point) -> Point {
+        self.point_at(self.param(p).clamp(0.0, 1.0))
+    }
+
+    /// Return the distance from p to the closest point on this line.
+    pub fn distance_to_point(&self, p: Point) -> f64 {
+        self.closest_point(p).distance_to(&p)
+    }
+}
+
+impl<T: Scalar> PartialEq for Line<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b
+    }
 }
 
-impl fmt::Display for Line {
+impl<T: fmt::Display> fmt::Display for Line<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "line({} -> {})", self.a, self.b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Line, SegmentIntersection, Side};
+    use crate::{point::Point, tests::assert_f64};
+
+    fn line(ax: f64, ay: f64, bx: f64, by: f64) -> Line {
+        Line::new(Point::new(ax, ay), Point::new(bx, by))
+    }
+
+    #[test]
+    fn measurements() {
+        let l = line(1.0, 1.0, 4.0, 5.0);
+
+        assert_f64!(l.length(), 5.0);
+        assert_eq!(l.midpoint(), Point::new(2.5, 3.0));
+        assert_eq!(l.direction(), Point::new(0.6, 0.8));
+        assert_eq!(l.normal(), Point::new(-0.8, 0.6));
+        assert_eq!(l.point_at(0.0), l.a);
+        assert_eq!(l.point_at(1.0), l.b);
+        assert_eq!(l.point_at(2.0), Point::new(7.0, 9.0));
+    }
+
+    #[test]
+    fn reversed() {
+        let l = line(1.0, 1.0, 4.0, 5.0).reversed();
+
+        assert_eq!(l.a, Point::new(4.0, 5.0));
+        assert_eq!(l.b, Point::new(1.0, 1.0));
+        assert_eq!(l.normal(), Point::new(0.8, -0.6));
+    }
+
+    macro_rules! closest_point_tests {
+        ($($name:ident: $line:expr, $p:expr, $expected:expr, $distance:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($line.closest_point($p), $expected);
+                    assert_f64!($line.distance_to_point($p), $distance);
+                }
+            )*
+        };
+    }
+
+    closest_point_tests!(
+        closest_middle: line(0.0, 0.0, 4.0, 0.0), Point::new(1.0, 3.0), Point::new(1.0, 0.0), 3.0,
+        closest_on_line: line(0.0, 0.0, 4.0, 4.0), Point::new(1.0, 1.0), Point::new(1.0, 1.0), 0.0,
+        closest_diagonal: line(0.0, 0.0, 4.0, 4.0), Point::new(0.0, 2.0), Point::new(1.0, 1.0), 2.0_f64.sqrt(),
+        closest_before_start: line(0.0, 0.0, 4.0, 0.0), Point::new(-3.0, 4.0), Point::new(0.0, 0.0), 5.0,
+        closest_past_end: line(0.0, 0.0, 4.0, 0.0), Point::new(5.0, -1.0), Point::new(4.0, 0.0), 2.0_f64.sqrt(),
+        closest_zero_length: line(1.0, 1.0, 1.0, 1.0), Point::new(4.0, 5.0), Point::new(1.0, 1.0), 5.0,
+    );
+
+    macro_rules! intersection_tests {
+        ($($name:ident: $a:expr, $b:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($a.intersection(&$b), $expected);
+                }
+            )*
+        };
+    }
+
+    intersection_tests!(
+        apart: line(0.0, 0.0, 1.0, 1.0), line(1.0, 0.0, 2.0, 1.0), SegmentIntersection::None,
+        crossing: line(0.0, 0.0, 4.0, 4.0), line(0.0, 4.0, 4.0, 0.0),
+            SegmentIntersection::Point(Point::new(2.0, 2.0), 0.5, 0.5),
+        crossing_off_center: line(0.0, 0.0, 4.0, 0.0), line(1.0, -1.0, 1.0, 3.0),
+            SegmentIntersection::Point(Point::new(1.0, 0.0), 0.25, 0.25),
+        would_cross_past_end: line(1.0, 0.0, 1.0, 2.0), line(0.0, 3.0, 2.0, 3.0), SegmentIntersection::None,
+        end_on_other: line(0.0, 0.0, 4.0, 0.0), line(2.0, 0.0, 2.0, 5.0),
+            SegmentIntersection::Point(Point::new(2.0, 0.0), 0.5, 0.0),
+        ends_touch: line(0.0, 0.0, 1.0, 1.0), line(1.0, 1.0, 2.0, 0.0),
+            SegmentIntersection::Point(Point::new(1.0, 1.0), 1.0, 0.0),
+        parallel: line(0.0, 0.0, 2.0, 0.0), line(0.0, 1.0, 2.0, 1.0), SegmentIntersection::None,
+        collinear_apart: line(0.0, 0.0, 1.0, 0.0), line(2.0, 0.0, 3.0, 0.0), SegmentIntersection::None,
+        collinear_end_to_end: line(0.0, 0.0, 1.0, 0.0), line(1.0, 0.0, 3.0, 0.0),
+            SegmentIntersection::Point(Point::new(1.0, 0.0), 1.0, 0.0),
+        overlap_horizontal: line(0.0, 0.0, 2.0, 0.0), line(1.0, 0.0, 3.0, 0.0),
+            SegmentIntersection::Overlap(line(1.0, 0.0, 2.0, 0.0)),
+        overlap_vertical: line(1.0, 3.0, 1.0, 0.0), line(1.0, 1.0, 1.0, 5.0),
+            SegmentIntersection::Overlap(line(1.0, 3.0, 1.0, 1.0)),
+        overlap_contained: line(0.0, 0.0, 4.0, 4.0), line(3.0, 3.0, 1.0, 1.0),
+            SegmentIntersection::Overlap(line(1.0, 1.0, 3.0, 3.0)),
+        overlap_containing: line(1.0, 1.0, 3.0, 3.0), line(0.0, 0.0, 4.0, 4.0),
+            SegmentIntersection::Overlap(line(1.0, 1.0, 3.0, 3.0)),
+        overlap_same: line(0.0, 0.0, 2.0, 1.0), line(2.0, 1.0, 0.0, 0.0),
+            SegmentIntersection::Overlap(line(0.0, 0.0, 2.0, 1.0)),
+        zero_length_on_line: line(1.0, 1.0, 1.0, 1.0), line(0.0, 0.0, 4.0, 4.0),
+            SegmentIntersection::Point(Point::new(1.0, 1.0), 0.0, 0.25),
+        zero_length_off_line: line(1.0, 2.0, 1.0, 2.0), line(0.0, 0.0, 4.0, 4.0), SegmentIntersection::None,
+    );
+
+    #[test]
+    fn crossing_params() {
+        let a = line(0.0, 0.0, 3.0, 1.0);
+        let b = line(1.0, -2.0, 2.0, 4.0);
+
+        match a.intersection(&b) {
+            SegmentIntersection::Point(p, t, u) => {
+                assert_eq!(p, a.point_at(t));
+                assert_eq!(p, b.point_at(u));
+                assert_eq!(b.intersection(&a), SegmentIntersection::Point(p, u, t));
+            }
+            other => panic!("expected a point, got {:?}", other),
+        }
+    }
+
+    macro_rules! side_of_tests {
+        ($($name:ident: $line:expr, $p:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($line.side_of($p), $expected);
+                }
+            )*
+        };
+    }
+
+    side_of_tests!(
+        side_left: line(0.0, 0.0, 2.0, 0.0), Point::new(1.0, 1.0), Side::Left,
+        side_right: line(0.0, 0.0, 2.0, 0.0), Point::new(1.0, -1.0), Side::Right,
+        side_on: line(0.0, 0.0, 2.0, 2.0), Point::new(1.0, 1.0), Side::On,
+        side_on_past_end: line(0.0, 0.0, 2.0, 2.0), Point::new(5.0, 5.0), Side::On,
+        side_reversed: line(2.0, 0.0, 0.0, 0.0), Point::new(1.0, 1.0), Side::Right,
+        side_just_left: line(0.0, 0.0, 1.0, 1.0), Point::new(0.5, 0.5 + f64::EPSILON / 2.0), Side::Left,
+        side_integer: Line::new(Point::from_xy(0_i64, 0), Point::from_xy(1 << 40, 1)), Point::from_xy(1 << 41, 3), Side::Left,
+    );
+}
@@ -0,0 +1,658 @@
+//! Collections of polygons
+use std::fmt;
+
+use crate::{
+    boundingbox::BoundingBox,
+    error::{self, Error},
+    overlay::{self, Operation},
+    point::Point,
+    polygon::{FillRule, Polygon},
+    polygonwithholes::PolygonWithHoles,
+    scalar::Scalar,
+    tolerance::Tolerance,
+};
+
+/// A collection of polygons that don't overlap each other, treated as one shape.
+///
+/// This is what the boolean operations return as they can produce any number of separate pieces.
+///
+/// Like [`Polygon`] containment and intersection checks work with any [`Scalar`] coordinates, areas,
+/// transformations and boolean operations need `f64` ones, and `i64` coordinates have exact boolean operations.
+#[derive(Debug, Clone, Default)]
+pub struct MultiPolygon<T = f64> {
+    pub polygons: Vec<PolygonWithHoles<T>>,
+}
+
+impl<T: Scalar> MultiPolygon<T> {
+    pub fn new(polygons: Vec<PolygonWithHoles<T>>) -> Self {
+        MultiPolygon { polygons }
+    }
+
+    /// Every ring, exteriors and holes, of every polygon in this collection.
+    pub(crate) fn rings(&self) -> Vec<&[Point<T>]> {
+        let mut result = Vec::new();
+        for p in self.polygons.iter() {
+            result.push(p.exterior.points.as_slice());
+            for hole in p.interiors.iter() {
+                result.push(hole.points.as_slice());
+            }
+        }
+        result
+    }
+
+    /// Return the number of polygons in this collection
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Returns true if there are no polygons in this collection
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// Iterate over the polygons in this collection
+    pub fn iter(&self) -> std::slice::Iter<'_, PolygonWithHoles<T>> {
+        self.polygons.iter()
+    }
+
+    /// Return the bounding box around all the polygons, or None if there are no polygons.
+    pub fn bounds(&self) -> Option<BoundingBox<T>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut min = Point::from_xy(T::MAX, T::MAX);
+        let mut max = Point::from_xy(T::MIN, T::MIN);
+        for p in self.polygons.iter() {
+            let bounds = p.bounds();
+            min = bounds.a.min(&min);
+            max = bounds.b.max(&max);
+        }
+
+        Some(BoundingBox::new(min, max))
+    }
+
+    /// Contains returns true if the point p is inside any of the polygons
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.polygons.iter().any(|poly| poly.contains(p))
+    }
+
+    /// Returns true if any of the polygons in the other collection overlap any of the polygons in this one.
+    pub fn intersects(&self, other: &MultiPolygon<T>) -> bool {
+        self.polygons
+            .iter()
+            .any(|a| other.polygons.iter().any(|b| a.intersects(b)))
+    }
+
+    /// Move all the polygons by point p
+    pub fn translate(&self, p: Point<T>) -> MultiPolygon<T> {
+        self.polygons.iter().map(|poly| poly.translate(p)).collect()
+    }
+}
+
+impl MultiPolygon {
+    /// Create an empty multi polygon
+    pub fn empty() -> Self {
+        MultiPolygon {
+            polygons: Vec::new(),
+        }
+    }
+
+    /// Build a multi polygon out of the rings produced by the overlay
+    pub(crate) fn from_rings(rings: Vec<Vec<Point>>) -> Self {
+        MultiPolygon::new(PolygonWithHoles::from_rings(
+            rings.into_iter().map(Polygon::new).collect(),
+        ))
+    }
+
+    /// Return the total area of all the polygons
+    pub fn area(&self) -> f64 {
+        self.polygons.iter().map(|p| p.area()).sum()
+    }
+
+    /// Return the point average of the exteriors of all the polygons, or None if there aren't any polygons.
+    pub fn center(&self) -> Option<Point> {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut count = 0;
+
+        for p in self.polygons.iter().flat_map(|p| p.exterior.points.iter()) {
+            x += p.x;
+            y += p.y;
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        let len = count as f64;
+
+        Some(Point::new(x / len, y / len))
+    }
+
+    /// Rotate all the polygons counter clockwise around their shared center point by angle radians
+    pub fn rotate_around_center(&self, angle: f64) -> MultiPolygon {
+        let Some(center) = self.center() else {
+            return MultiPolygon::empty();
+        };
+        let center_inv = center.invert();
+
+        self.polygons
+            .iter()
+            .map(|poly| {
+                poly.translate(center_inv)
+                    .rotate_around_origin(angle)
+                    .translate(center)
+            })
+            .collect()
+    }
+
+    /// Rotate all the polygons counter clockwise around the origin by angle radians
+    pub fn rotate_around_origin(&self, angle: f64) -> MultiPolygon {
+        self.polygons
+            .iter()
+            .map(|poly| poly.rotate_around_origin(angle))
+            .collect()
+    }
+
+    /// Scale all the polygons away from the anchor point, multiplying distances along x by sx and along y by sy.
+    ///
+    /// Negative scales mirror the polygons, their exteriors and holes keep going the same way around.
+    pub fn scale_around(&self, anchor: Point, sx: f64, sy: f64) -> MultiPolygon {
+        self.polygons
+            .iter()
+            .map(|poly| poly.scale_around(anchor, sx, sy))
+            .collect()
+    }
+
+    /// Scale all the polygons away from their shared center point, see `scale_around`
+    pub fn scale_around_center(&self, sx: f64, sy: f64) -> MultiPolygon {
+        match self.center() {
+            Some(center) => self.scale_around(center, sx, sy),
+            None => MultiPolygon::empty(),
+        }
+    }
+
+    /// Scale all the polygons away from the origin, see `scale_around`
+    pub fn scale_around_origin(&self, sx: f64, sy: f64) -> MultiPolygon {
+        self.scale_around(Point::zero(), sx, sy)
+    }
+
+    /// Create the union of this and the other collection of polygons.
+    pub fn union(&self, other: &MultiPolygon) -> MultiPolygon {
+        self.overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this and the other collection of polygons, the area covered by both of them.
+    pub fn intersection(&self, other: &MultiPolygon) -> MultiPolygon {
+        self.overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other collection of polygons from this one.
+    pub fn difference(&self, other: &MultiPolygon) -> MultiPolygon {
+        self.overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this and the other collection of polygons,
+    /// the areas covered by exactly one of them.
+    pub fn symmetric_difference(&self, other: &MultiPolygon) -> MultiPolygon {
+        self.overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this and the other collection of polygons.
+    /// Returns an error if any polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_union(&self, other: &MultiPolygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this and the other collection of polygons.
+    /// Returns an error if any polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_intersection(&self, other: &MultiPolygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other collection of polygons from this one.
+    /// Returns an error if any polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_difference(&self, other: &MultiPolygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this and the other collection of polygons.
+    /// Returns an error if any polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_symmetric_difference(&self, other: &MultiPolygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this and the other collection of polygons,
+    /// snapping all their points to a grid with the tolerance's epsilon as the spacing first.
+    pub fn union_with_tolerance(&self, other: &MultiPolygon, tolerance: Tolerance) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Union, tolerance)
+    }
+
+    /// Create the intersection of this and the other collection of polygons,
+    /// snapping all their points to the tolerance first.
+    pub fn intersection_with_tolerance(
+        &self,
+        other: &MultiPolygon,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Intersection, tolerance)
+    }
+
+    /// Subtract the other collection of polygons from this one, snapping all their points to the tolerance first.
+    pub fn difference_with_tolerance(
+        &self,
+        other: &MultiPolygon,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Difference, tolerance)
+    }
+
+    /// Create the symmetric difference of this and the other collection of polygons,
+    /// snapping all their points to the tolerance first.
+    pub fn symmetric_difference_with_tolerance(
+        &self,
+        other: &MultiPolygon,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::SymmetricDifference, tolerance)
+    }
+
+    fn try_overlay(&self, other: &MultiPolygon, op: Operation) -> Result<MultiPolygon, Error> {
+        for ring in self.rings().into_iter().chain(other.rings()) {
+            error::check_finite(ring)?;
+        }
+        Ok(MultiPolygon::from_rings(overlay::try_overlay(
+            &self.rings(),
+            &other.rings(),
+            op,
+            FillRule::EvenOdd,
+        )?))
+    }
+
+    fn overlay_with_tolerance(
+        &self,
+        other: &MultiPolygon,
+        op: Operation,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        let snap = |rings: Vec<&[Point]>| -> Vec<Vec<Point>> {
+            rings
+                .into_iter()
+                .map(|ring| ring.iter().map(|p| p.snap(tolerance)).collect())
+                .collect()
+        };
+        let a = snap(self.rings());
+        let b = snap(other.rings());
+
+        MultiPolygon::from_rings(overlay::overlay(
+            &a.iter().map(|r| r.as_slice()).collect::<Vec<_>>(),
+            &b.iter().map(|r| r.as_slice()).collect::<Vec<_>>(),
+            op,
+            FillRule::EvenOdd,
+        ))
+    }
+
+    /// Convert all the polygons to i64 coordinates for the exact integer boolean operations, multiplying every
+    /// coordinate by scale and rounding it to the nearest whole number.
+    ///
+    /// Returns an error if any coordinate is not finite or ends up bigger than 2^60.
+    pub fn to_integer(&self, scale: f64) -> Result<MultiPolygon<i64>, Error> {
+        self.polygons.iter().map(|p| p.to_integer(scale)).collect()
+    }
+
+    fn overlay(&self, other: &MultiPolygon, op: Operation) -> MultiPolygon {
+        // even odd means holes work whichever way round their points go
+        MultiPolygon::from_rings(overlay::overlay(
+            &self.rings(),
+            &other.rings(),
+            op,
+            FillRule::EvenOdd,
+        ))
+    }
+}
+
+/// Boolean operations on integer coordinates, see the integer operations on [`Polygon`] for the details.
+impl MultiPolygon<i64> {
+    /// Build a multi polygon out of the rings produced by the integer overlay
+    pub(crate) fn from_integer_rings(rings: Vec<Vec<Point<i64>>>) -> Self {
+        MultiPolygon::new(PolygonWithHoles::from_integer_rings(
+            rings.into_iter().map(Polygon::new).collect(),
+        ))
+    }
+
+    /// Return twice the total area of all the polygons, worked out exactly.
+    pub fn doubled_area(&self) -> i128 {
+        self.polygons.iter().map(|p| p.doubled_area()).sum()
+    }
+
+    /// Convert all the polygons to f64 coordinates, dividing every coordinate by scale.
+    pub fn to_float(&self, scale: f64) -> MultiPolygon {
+        self.polygons.iter().map(|p| p.to_float(scale)).collect()
+    }
+
+    /// Create the union of this and the other collection of polygons.
+    pub fn union(&self, other: &MultiPolygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this and the other collection of polygons, the area covered by both of them.
+    pub fn intersection(&self, other: &MultiPolygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other collection of polygons from this one.
+    pub fn difference(&self, other: &MultiPolygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this and the other collection of polygons,
+    /// the areas covered by exactly one of them.
+    pub fn symmetric_difference(&self, other: &MultiPolygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this and the other collection of polygons.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_union(&self, other: &MultiPolygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this and the other collection of polygons.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_intersection(&self, other: &MultiPolygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other collection of polygons from this one.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_difference(&self, other: &MultiPolygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this and the other collection of polygons.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_symmetric_difference(
+        &self,
+        other: &MultiPolygon<i64>,
+    ) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::SymmetricDifference)
+    }
+
+    fn try_overlay(
+        &self,
+        other: &MultiPolygon<i64>,
+        op: Operation,
+    ) -> Result<MultiPolygon<i64>, Error> {
+        Ok(MultiPolygon::from_integer_rings(
+            overlay::try_integer_overlay(&self.rings(), &other.rings(), op)?,
+        ))
+    }
+
+    fn overlay(&self, other: &MultiPolygon<i64>, op: Operation) -> MultiPolygon<i64> {
+        MultiPolygon::from_integer_rings(overlay::integer_overlay(
+            &self.rings(),
+            &other.rings(),
+            op,
+        ))
+    }
+}
+
+impl<T: Scalar> PartialEq for MultiPolygon<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.polygons == other.polygons
+    }
+}
+
+impl<T: Scalar> From<Polygon<T>> for MultiPolygon<T> {
+    fn from(polygon: Polygon<T>) -> MultiPolygon<T> {
+        MultiPolygon::new(vec![polygon.into()])
+    }
+}
+
+impl<T: Scalar> From<PolygonWithHoles<T>> for MultiPolygon<T> {
+    fn from(polygon: PolygonWithHoles<T>) -> MultiPolygon<T> {
+        MultiPolygon::new(vec![polygon])
+    }
+}
+
+impl<T: Scalar> FromIterator<PolygonWithHoles<T>> for MultiPolygon<T> {
+    fn from_iter<I: IntoIterator<Item = PolygonWithHoles<T>>>(iter: I) -> Self {
+        MultiPolygon::new(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for MultiPolygon<T> {
+    type Item = PolygonWithHoles<T>;
+    type IntoIter = std::vec::IntoIter<PolygonWithHoles<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.polygons.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MultiPolygon<T> {
+    type Item = &'a PolygonWithHoles<T>;
+    type IntoIter = std::slice::Iter<'a, PolygonWithHoles<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.polygons.iter()
+    }
+}
+
+impl<T: Scalar> fmt::Display for MultiPolygon<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "MultiPoly(")?;
+
+        let mut first = true;
+        for p in self.polygons.iter() {
+            if !first {
+                write!(formatter, ", ")?;
+            } else {
+                first = false;
+            }
+            p.fmt(formatter)?;
+        }
+
+        write!(formatter, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        error::Error,
+        point::Point,
+        polygon::Polygon,
+        tests::{assert_f64, square},
+        tolerance::Tolerance,
+    };
+
+    use super::MultiPolygon;
+
+    fn two_squares() -> MultiPolygon {
+        vec![square(0.0, 0.0, 1.0).into(), square(2.0, 0.0, 1.0).into()]
+            .into_iter()
+            .collect()
+    }
+
+    macro_rules! contains_tests {
+        ($($name:ident: $test_point:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(two_squares().contains($test_point), $expected);
+                }
+            )*
+        };
+    }
+
+    contains_tests!(
+        in_first: Point::new(0.5, 0.5), true,
+        in_second: Point::new(2.5, 0.5), true,
+        between: Point::new(1.5, 0.5), false,
+    );
+
+    #[test]
+    fn area() {
+        assert_f64!(two_squares().area(), 2.0);
+        assert_f64!(MultiPolygon::empty().area(), 0.0);
+    }
+
+    #[test]
+    fn bounds() {
+        let bounds = two_squares().bounds().unwrap();
+        assert_eq!(bounds.a, Point::zero());
+        assert_eq!(bounds.b, Point::new(3.0, 1.0));
+
+        assert!(MultiPolygon::empty().bounds().is_none());
+    }
+
+    #[test]
+    fn intersects() {
+        let between = MultiPolygon::from(square(1.25, 0.25, 0.5));
+        let over = MultiPolygon::from(square(2.5, 0.5, 1.0));
+
+        assert!(!two_squares().intersects(&between));
+        assert!(two_squares().intersects(&over));
+    }
+
+    #[test]
+    fn iterate() {
+        let multi = two_squares();
+        assert_eq!(multi.len(), 2);
+        assert_eq!(multi.iter().count(), 2);
+        assert_eq!((&multi).into_iter().count(), 2);
+        assert_eq!(multi.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn translate() {
+        let result = two_squares().translate(Point::new(0.0, 1.0));
+
+        let expected: MultiPolygon =
+            vec![square(0.0, 1.0, 1.0).into(), square(2.0, 1.0, 1.0).into()]
+                .into_iter()
+                .collect();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(two_squares().center(), Some(Point::new(1.5, 0.5)));
+        assert_eq!(MultiPolygon::empty().center(), None);
+    }
+
+    #[test]
+    fn empty_around_center() {
+        let empty = MultiPolygon::empty();
+
+        assert!(empty.rotate_around_center(1.0).is_empty());
+        assert!(empty.scale_around_center(2.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn rotate_around_center() {
+        let result = two_squares().rotate_around_center(90.0_f64.to_radians());
+
+        assert!(result.contains(Point::new(1.5, -0.5)));
+        assert!(result.contains(Point::new(1.5, 1.5)));
+        assert!(!result.contains(Point::new(1.5, 0.5)));
+    }
+
+    #[test]
+    fn rotate_around_origin() {
+        let result = two_squares().rotate_around_origin(90.0_f64.to_radians());
+
+        assert!(result.contains(Point::new(-0.5, 2.5)));
+        assert!(!result.contains(Point::new(2.5, 0.5)));
+    }
+
+    #[test]
+    fn scale_around_center() {
+        let result = two_squares().scale_around_center(2.0, -1.0);
+
+        assert_f64!(result.area(), 4.0);
+        assert!(result.contains(Point::new(-0.5, 0.5)));
+        assert!(result.contains(Point::new(3.5, 0.5)));
+        assert!(!result.contains(Point::new(1.5, 0.5)));
+        // mirroring them doesn't change which way they go around, so they still work with the boolean operations
+        assert_f64!(result.union(&two_squares()).area(), 5.0);
+    }
+
+    #[test]
+    fn scale_around_origin() {
+        let result = two_squares().scale_around_origin(0.5, 0.5);
+
+        assert_f64!(result.area(), 0.5);
+        assert!(result.contains(Point::new(1.25, 0.25)));
+        assert!(!result.contains(Point::new(2.5, 0.5)));
+    }
+
+    #[test]
+    fn try_difference_not_finite() {
+        let cut = MultiPolygon::from(square(f64::NAN, 0.0, 1.0));
+
+        assert!(matches!(
+            two_squares().try_difference(&cut),
+            Err(Error::NonFiniteCoordinate(_))
+        ));
+    }
+
+    #[test]
+    fn union_joins_pieces() {
+        let bridge = MultiPolygon::from(square(0.5, 0.25, 2.0));
+
+        let result = two_squares().union(&bridge);
+
+        assert_eq!(result.len(), 1);
+        assert_f64!(result.area(), 5.25);
+    }
+
+    #[test]
+    fn difference_of_multi() {
+        let cut = MultiPolygon::from(square(0.5, -1.0, 2.0));
+
+        let result = two_squares().difference(&cut);
+
+        assert_eq!(result.len(), 2);
+        assert_f64!(result.area(), 1.0);
+    }
+
+    #[test]
+    fn intersection_with_holes() {
+        let donut = square(0.0, 0.0, 3.0).difference(&square(1.0, 1.0, 1.0));
+        let bar = MultiPolygon::from(Polygon::new(vec![
+            Point::new(0.0, 1.25),
+            Point::new(0.0, 1.75),
+            Point::new(3.0, 1.75),
+            Point::new(3.0, 1.25),
+        ]));
+
+        let result = donut.intersection(&bar);
+
+        assert_eq!(result.len(), 2);
+        assert_f64!(result.area(), 1.0);
+        assert_f64!(result.symmetric_difference(&donut).area(), 7.0);
+    }
+
+    #[test]
+    fn union_with_tolerance() {
+        let gap = MultiPolygon::from(square(1.0 + 1e-9, 0.0, 1.0));
+
+        assert_eq!(two_squares().union(&gap).len(), 2);
+
+        let result = two_squares().union_with_tolerance(&gap, Tolerance::new(1e-6, 2));
+        assert_eq!(result.len(), 1);
+        assert_f64!(result.area(), 3.0, epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,1104 @@
+//! Boolean overlay of polygons. None of this is exposed outside the library
+//!
+//! Every edge of both inputs is split wherever it touches or crosses another edge, so that what is left is a set of
+//! atomic edges that only meet at their end points. For each atomic edge we then work out the winding number of the
+//! two inputs on either side of it. An edge is part of the result if the operation gives a different answer on its two
+//! sides. Finally the result edges are linked back together into rings.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::ops::ControlFlow;
+
+use crate::error::Error;
+use crate::geom::{self, Orientation};
+use crate::point::Point;
+use crate::polygon::FillRule;
+use crate::scalar::Scalar;
+use crate::sweep;
+
+/// The boolean operation to perform on the two inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+impl Operation {
+    fn apply(&self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            Operation::Union => in_a || in_b,
+            Operation::Intersection => in_a && in_b,
+            Operation::Difference => in_a && !in_b,
+            Operation::SymmetricDifference => in_a != in_b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    A,
+    B,
+}
+
+/// An edge of the arrangement. lo -> hi is the canonical direction, pointing up, or towards -x for horizontal edges.
+/// With that direction the ray casting in winding_right always measures the right hand side of the edge.
+struct AtomicEdge {
+    lo: usize,
+    hi: usize,
+    // how many times the edges of each input run along this edge, counted +1 for lo -> hi and -1 for hi -> lo
+    winding_a: i32,
+    winding_b: i32,
+}
+
+/// Perform a boolean operation on the rings in a and the rings in b.
+///
+/// Rings may be in either direction, the fill rule decides which points are inside each input.
+/// Returned rings run clockwise around the filled area, so outer boundaries are clockwise and holes counter clockwise.
+pub fn overlay<T: Coordinate>(
+    a: &[&[Point<T>]],
+    b: &[&[Point<T>]],
+    op: Operation,
+    rule: FillRule,
+) -> Vec<Vec<Point<T>>> {
+    overlay_rings(a, b, op, rule).0
+}
+
+/// Perform a boolean operation like `overlay`, but return an error rather than leaving out any parts of the result
+/// that couldn't be joined up into closed rings, or returning a ring that touches or crosses itself.
+pub fn try_overlay<T: Coordinate>(
+    a: &[&[Point<T>]],
+    b: &[&[Point<T>]],
+    op: Operation,
+    rule: FillRule,
+) -> Result<Vec<Vec<Point<T>>>, Error> {
+    match overlay_rings(a, b, op, rule) {
+        (rings, true) => check_simple(rings),
+        (_, false) => Err(Error::OverlayFailed),
+    }
+}
+
+/// The rings of the result, and whether every result edge ended up in one of them.
+fn overlay_rings<T: Coordinate>(
+    a: &[&[Point<T>]],
+    b: &[&[Point<T>]],
+    op: Operation,
+    rule: FillRule,
+) -> (Vec<Vec<Point<T>>>, bool) {
+    let mut edges = Vec::new();
+    collect_edges(a, Source::A, &mut edges);
+    collect_edges(b, Source::B, &mut edges);
+
+    let (nodes, atomic, settled) = split_edges(&edges);
+
+    // work out which side of each edge is inside the result.
+    let mut result_edges = Vec::new();
+    for (edge, (right_a, right_b)) in atomic.iter().zip(winding_right(&nodes, &atomic)) {
+        let inside_right = op.apply(rule.fills(right_a), rule.fills(right_b));
+        let inside_left = op.apply(
+            rule.fills(right_a + edge.winding_a),
+            rule.fills(right_b + edge.winding_b),
+        );
+
+        if inside_right && !inside_left {
+            result_edges.push((edge.lo, edge.hi));
+        } else if inside_left && !inside_right {
+            result_edges.push((edge.hi, edge.lo));
+        }
+    }
+
+    let (rings, closed) = link_rings(&nodes, &result_edges);
+    (rings, settled && closed)
+}
+
+/// The furthest from the origin integer coordinates can be for the boolean operations.
+pub const MAX_INTEGER_COORDINATE: i64 = 1 << 60;
+
+/// Perform a boolean operation on integer rings using the even odd fill rule.
+///
+/// The coordinates are doubled while working it out, see the `Coordinate` implementation for i64, so crossing points
+/// get rounded to the nearest whole number. This will panic if any coordinate is bigger than `MAX_INTEGER_COORDINATE`.
+pub fn integer_overlay(
+    a: &[&[Point<i64>]],
+    b: &[&[Point<i64>]],
+    op: Operation,
+) -> Vec<Vec<Point<i64>>> {
+    let double = |rings| double(rings).unwrap_or_else(|e| panic!("{}", e));
+    let (a, b) = (double(a), double(b));
+
+    halve(overlay(&slices(&a), &slices(&b), op, FillRule::EvenOdd))
+}
+
+/// Perform a boolean operation like `integer_overlay`, but return an error rather than panicking if a coordinate is
+/// too big, leaving out parts of the result that couldn't be joined up into closed rings, or returning a ring that
+/// touches or crosses itself.
+pub fn try_integer_overlay(
+    a: &[&[Point<i64>]],
+    b: &[&[Point<i64>]],
+    op: Operation,
+) -> Result<Vec<Vec<Point<i64>>>, Error> {
+    let (a, b) = (double(a)?, double(b)?);
+
+    // the rings are checked after halving, as rounding the coordinates back down could make them touch.
+    match overlay_rings(&slices(&a), &slices(&b), op, FillRule::EvenOdd) {
+        (rings, true) => check_simple(halve(rings)),
+        (_, false) => Err(Error::OverlayFailed),
+    }
+}
+
+/// Pass the rings through if they are all simple, otherwise the overlay went wrong somewhere.
+fn check_simple<T: Scalar>(rings: Vec<Vec<Point<T>>>) -> Result<Vec<Vec<Point<T>>>, Error> {
+    if rings.iter().all(|ring| is_simple(ring)) {
+        Ok(rings)
+    } else {
+        Err(Error::OverlayFailed)
+    }
+}
+
+/// Does this ring only touch itself where neighbouring sides meet, without folding back along each other.
+fn is_simple<T: Scalar>(ring: &[Point<T>]) -> bool {
+    let n = ring.len();
+    if n < 3 {
+        return false;
+    }
+
+    let sides: Vec<(Point<T>, Point<T>)> = (0..n).map(|i| (ring[i], ring[(i + 1) % n])).collect();
+    sweep::intersecting_pairs(&sides, |i, j| {
+        let (first, second) = if j == i + 1 {
+            (sides[i], sides[j])
+        } else if i == 0 && j == n - 1 {
+            (sides[j], sides[i])
+        } else {
+            return ControlFlow::Break(());
+        };
+
+        // neighbouring sides (a, b) and (b, c) always touch at b, anything more means they double back.
+        let (a, b, c) = (first.0, first.1, second.1);
+        if same(a, c) || geom::strictly_on_segment(a, b, c) || geom::strictly_on_segment(b, c, a) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_continue()
+}
+
+fn double(rings: &[&[Point<i64>]]) -> Result<Vec<Vec<Point<i64>>>, Error> {
+    rings
+        .iter()
+        .map(|ring| {
+            ring.iter()
+                .map(|p| {
+                    let limit = MAX_INTEGER_COORDINATE as u64;
+                    if p.x.unsigned_abs() > limit || p.y.unsigned_abs() > limit {
+                        return Err(Error::CoordinateOutOfRange(p.cast()));
+                    }
+                    Ok(Point::from_xy(p.x * 2, p.y * 2))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn halve(rings: Vec<Vec<Point<i64>>>) -> Vec<Vec<Point<i64>>> {
+    rings
+        .into_iter()
+        .map(|ring| {
+            ring.iter()
+                .map(|p| Point::from_xy(p.x / 2, p.y / 2))
+                .collect()
+        })
+        .collect()
+}
+
+fn slices<T>(rings: &[Vec<Point<T>>]) -> Vec<&[Point<T>]> {
+    rings.iter().map(|r| r.as_slice()).collect()
+}
+
+fn same<T: Scalar>(a: Point<T>, b: Point<T>) -> bool {
+    a.x == b.x && a.y == b.y
+}
+
+fn collect_edges<T: Scalar>(
+    rings: &[&[Point<T>]],
+    source: Source,
+    edges: &mut Vec<(Point<T>, Point<T>, Source)>,
+) {
+    for ring in rings {
+        for i in 0..ring.len() {
+            let a = ring[i];
+            let b = ring[(i + 1) % ring.len()];
+            if !same(a, b) {
+                edges.push((a, b, source));
+            }
+        }
+    }
+}
+
+/// How many rounds of splitting the pieces again are tried before giving up on them settling down.
+const MAX_SPLIT_ROUNDS: usize = 32;
+
+/// Split all the edges where they touch or cross each other and merge any that end up on top of each other.
+///
+/// Crossing points get rounded, so splitting an edge at one bends it very slightly, and crossings that should be at
+/// the same place can come out a tiny distance apart. So points closer together than the merge distance become the
+/// same node, and the pieces are checked again after each round of splitting until none of them cross any more.
+/// The last value is false if that never happened.
+fn split_edges<T: Coordinate>(
+    edges: &[(Point<T>, Point<T>, Source)],
+) -> (Vec<Point<T>>, Vec<AtomicEdge>, bool) {
+    let largest = edges
+        .iter()
+        .flat_map(|e| [e.0, e.1])
+        .map(|p| p.x.to_f64().abs().max(p.y.to_f64().abs()))
+        .fold(0.0, f64::max);
+    let mut nodes = Nodes::new(T::merge_distance(largest));
+
+    let mut pieces: Vec<(usize, usize, Source)> = edges
+        .iter()
+        .map(|(a, b, source)| (nodes.node_for(*a), nodes.node_for(*b), *source))
+        .filter(|(from, to, _)| from != to)
+        .collect();
+
+    let mut settled = false;
+    for _ in 0..MAX_SPLIT_ROUNDS {
+        let splits = find_splits(&mut nodes, &pieces);
+        if splits.iter().all(|s| s.is_empty()) {
+            settled = true;
+            break;
+        }
+
+        let mut next = Vec::with_capacity(pieces.len());
+        for ((from, to, source), mut inner) in pieces.into_iter().zip(splits) {
+            let (a, b) = (nodes.points[from], nodes.points[to]);
+            inner.sort_by(|p, q| T::cmp_along(a, b, nodes.points[*p], nodes.points[*q]));
+
+            let mut chain = vec![from];
+            chain.extend(inner);
+            chain.push(to);
+            chain.dedup();
+            for pair in chain.windows(2) {
+                next.push((pair[0], pair[1], source));
+            }
+        }
+        pieces = next;
+    }
+
+    let mut atomic: Vec<AtomicEdge> = Vec::new();
+    let mut atomic_index = HashMap::new();
+    for (from, to, source) in pieces {
+        let (lo, hi, direction) = if points_up(nodes.points[from], nodes.points[to]) {
+            (from, to, 1)
+        } else {
+            (to, from, -1)
+        };
+
+        let index = *atomic_index.entry((lo, hi)).or_insert_with(|| {
+            atomic.push(AtomicEdge {
+                lo,
+                hi,
+                winding_a: 0,
+                winding_b: 0,
+            });
+            atomic.len() - 1
+        });
+
+        match source {
+            Source::A => atomic[index].winding_a += direction,
+            Source::B => atomic[index].winding_b += direction,
+        }
+    }
+
+    (nodes.points, atomic, settled)
+}
+
+/// The nodes each piece has to be split at, where other pieces touch or cross it.
+fn find_splits<T: Coordinate>(
+    nodes: &mut Nodes<T>,
+    pieces: &[(usize, usize, Source)],
+) -> Vec<Vec<usize>> {
+    let mut splits: Vec<Vec<usize>> = vec![Vec::new(); pieces.len()];
+    let segments: Vec<(Point<T>, Point<T>)> = pieces
+        .iter()
+        .map(|(from, to, _)| (nodes.points[*from], nodes.points[*to]))
+        .collect();
+
+    let _ = sweep::intersecting_pairs(&segments, |i, j| {
+        let (a, b) = segments[i];
+        let (c, d) = segments[j];
+        let ((from_i, to_i, _), (from_j, to_j, _)) = (pieces[i], pieces[j]);
+
+        // touching end points and collinear overlaps.
+        for (p, node) in [(c, from_j), (d, to_j)] {
+            if geom::strictly_on_segment(a, b, p) {
+                splits[i].push(node);
+            }
+        }
+        for (p, node) in [(a, from_i), (b, to_i)] {
+            if geom::strictly_on_segment(c, d, p) {
+                splits[j].push(node);
+            }
+        }
+
+        // proper crossings. The same node is used for both so the pieces join up exactly.
+        let o1 = geom::orientation(a, b, c);
+        let o2 = geom::orientation(a, b, d);
+        let o3 = geom::orientation(c, d, a);
+        let o4 = geom::orientation(c, d, b);
+        if o1 != Orientation::Collinear
+            && o2 != Orientation::Collinear
+            && o3 != Orientation::Collinear
+            && o4 != Orientation::Collinear
+            && o1 != o2
+            && o3 != o4
+        {
+            let node = nodes.node_for(T::crossing_point(a, b, c, d));
+            if node != from_i && node != to_i {
+                splits[i].push(node);
+            }
+            if node != from_j && node != to_j {
+                splits[j].push(node);
+            }
+        }
+
+        ControlFlow::Continue(())
+    });
+
+    for inner in splits.iter_mut() {
+        inner.sort_unstable();
+        inner.dedup();
+    }
+    splits
+}
+
+/// The points of the arrangement, where a point within the merge distance of an existing node becomes that node.
+struct Nodes<T> {
+    points: Vec<Point<T>>,
+    // the nodes in each cell of a grid the size of the merge distance, so only the nearby ones need checking
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    distance: f64,
+}
+
+impl<T: Coordinate> Nodes<T> {
+    fn new(distance: f64) -> Self {
+        Nodes {
+            points: Vec::new(),
+            cells: HashMap::new(),
+            distance,
+        }
+    }
+
+    fn node_for(&mut self, p: Point<T>) -> usize {
+        let (x, y) = T::cell(p, self.distance);
+        let reach = if self.distance > 0.0 { 1 } else { 0 };
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                let cell = (x.wrapping_add(dx), y.wrapping_add(dy));
+                let found = self.cells.get(&cell).and_then(|nodes| {
+                    nodes
+                        .iter()
+                        .find(|n| T::near(self.points[**n], p, self.distance))
+                });
+                if let Some(node) = found {
+                    return *node;
+                }
+            }
+        }
+
+        self.points.push(p);
+        let node = self.points.len() - 1;
+        self.cells.entry((x, y)).or_default().push(node);
+        node
+    }
+}
+
+/// Is a -> b the canonical direction for an edge
+fn points_up<T: Scalar>(a: Point<T>, b: Point<T>) -> bool {
+    a.y < b.y || (a.y == b.y && a.x > b.x)
+}
+
+/// Winding numbers of both inputs on the right hand side of every atomic edge.
+///
+/// This casts a ray towards +x from the midpoint of each edge. Vertices on the ray count as below it, so for a
+/// horizontal edge this measures just above the edge, which is its right hand side in the canonical direction. For
+/// every other edge it measures just to the +x side which is again the right hand side.
+///
+/// The rays are cast in order of height while keeping the edges that span the current height in a tree, ordered from
+/// left to right, so the windings of every edge a ray crosses can be added up by walking down the tree.
+fn winding_right<T: Coordinate>(nodes: &[Point<T>], atomic: &[AtomicEdge]) -> Vec<(i32, i32)> {
+    let lo = |e: usize| nodes[atomic[e].lo];
+    let hi = |e: usize| nodes[atomic[e].hi];
+
+    let midpoints: Vec<Point<T>> = (0..atomic.len())
+        .map(|e| T::midpoint(lo(e), hi(e)))
+        .collect();
+    let mut rays: Vec<usize> = (0..atomic.len()).collect();
+    rays.sort_by(|a, b| midpoints[*a].y.total_cmp(&midpoints[*b].y));
+
+    // horizontal edges can never be crossed by a horizontal ray
+    let mut starts: Vec<usize> = (0..atomic.len()).filter(|e| lo(*e).y < hi(*e).y).collect();
+    let mut ends = starts.clone();
+    starts.sort_by(|a, b| lo(*a).y.total_cmp(&lo(*b).y));
+    ends.sort_by(|a, b| hi(*a).y.total_cmp(&hi(*b).y));
+
+    let mut result = vec![(0, 0); atomic.len()];
+    let mut spanning = Spanning::new(nodes, atomic);
+    let mut added = vec![false; atomic.len()];
+    let mut finished = vec![false; atomic.len()];
+    let (mut next_start, mut next_end) = (0, 0);
+    for ray in rays {
+        let p = midpoints[ray];
+
+        // take out the finished edges first, so every edge in the tree spans the heights of all the others.
+        while next_end < ends.len() && hi(ends[next_end]).y <= p.y {
+            let e = ends[next_end];
+            if added[e] {
+                spanning.remove(e);
+            }
+            finished[e] = true;
+            next_end += 1;
+        }
+        while next_start < starts.len() && lo(starts[next_start]).y <= p.y {
+            let e = starts[next_start];
+            if !finished[e] {
+                spanning.insert(e);
+                added[e] = true;
+            }
+            next_start += 1;
+        }
+
+        result[ray] = spanning.winding_right_of(p, ray);
+    }
+
+    result
+}
+
+/// The edges spanning the current height in winding_right, ordered from left to right.
+///
+/// This is a treap, with priorities from a hash of the edge index, and every node keeps the total windings of the
+/// edges below it. The edges of the arrangement never cross, so two edges spanning the same height are always in the
+/// same order and can be compared using an end point of one of them.
+struct Spanning<'a, T> {
+    nodes: &'a [Point<T>],
+    atomic: &'a [AtomicEdge],
+    root: Option<usize>,
+    left: Vec<Option<usize>>,
+    right: Vec<Option<usize>>,
+    total: Vec<(i32, i32)>,
+}
+
+impl<'a, T: Coordinate> Spanning<'a, T> {
+    fn new(nodes: &'a [Point<T>], atomic: &'a [AtomicEdge]) -> Self {
+        Spanning {
+            nodes,
+            atomic,
+            root: None,
+            left: vec![None; atomic.len()],
+            right: vec![None; atomic.len()],
+            total: vec![(0, 0); atomic.len()],
+        }
+    }
+
+    fn insert(&mut self, e: usize) {
+        self.left[e] = None;
+        self.right[e] = None;
+        self.update(e);
+
+        let (before, after) = self.split(self.root, e);
+        let before = self.merge(before, Some(e));
+        self.root = self.merge(before, after);
+    }
+
+    fn remove(&mut self, e: usize) {
+        // everything left of e is split off, which leaves e as the first of the rest
+        let (before, after) = self.split(self.root, e);
+        let after = after.and_then(|t| self.remove_first(t));
+        self.root = self.merge(before, after);
+    }
+
+    /// The total windings of the edges to the right of p, leaving out the edge the ray is from.
+    fn winding_right_of(&self, p: Point<T>, ray: usize) -> (i32, i32) {
+        let mut sum = (0, 0);
+        let mut node = self.root;
+        while let Some(e) = node {
+            let side = if e == ray {
+                Orientation::Collinear
+            } else {
+                geom::orientation(self.lo(e), self.hi(e), p)
+            };
+
+            match side {
+                Orientation::AntiClockwise => {
+                    sum = add(add(sum, self.own(e)), self.total_of(self.right[e]));
+                    node = self.left[e];
+                }
+                Orientation::Clockwise => node = self.right[e],
+                Orientation::Collinear => {
+                    // everything right of an edge through p is right of p
+                    sum = add(sum, self.total_of(self.right[e]));
+                    break;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Split the tree into the edges to the left of e and everything else.
+    fn split(&mut self, tree: Option<usize>, e: usize) -> (Option<usize>, Option<usize>) {
+        let Some(t) = tree else {
+            return (None, None);
+        };
+
+        if self.cmp(t, e) == Ordering::Less {
+            let (before, after) = self.split(self.right[t], e);
+            self.right[t] = before;
+            self.update(t);
+            (Some(t), after)
+        } else {
+            let (before, after) = self.split(self.left[t], e);
+            self.left[t] = after;
+            self.update(t);
+            (before, Some(t))
+        }
+    }
+
+    /// Join two trees where everything in a is to the left of everything in b.
+    fn merge(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        match (a, b) {
+            (None, tree) | (tree, None) => tree,
+            (Some(a), Some(b)) => {
+                if priority(a) > priority(b) {
+                    self.right[a] = self.merge(self.right[a], Some(b));
+                    self.update(a);
+                    Some(a)
+                } else {
+                    self.left[b] = self.merge(Some(a), self.left[b]);
+                    self.update(b);
+                    Some(b)
+                }
+            }
+        }
+    }
+
+    fn remove_first(&mut self, t: usize) -> Option<usize> {
+        match self.left[t] {
+            None => self.right[t],
+            Some(left) => {
+                self.left[t] = self.remove_first(left);
+                self.update(t);
+                Some(t)
+            }
+        }
+    }
+
+    /// Is edge e to the left or right of edge f.
+    fn cmp(&self, e: usize, f: usize) -> Ordering {
+        if e == f {
+            return Ordering::Equal;
+        }
+
+        // the higher of the two bottom ends is within the height of both edges.
+        if self.lo(e).y >= self.lo(f).y {
+            side_of(self.lo(f), self.hi(f), self.lo(e), self.hi(e))
+        } else {
+            side_of(self.lo(e), self.hi(e), self.lo(f), self.hi(f)).reverse()
+        }
+    }
+
+    fn update(&mut self, e: usize) {
+        self.total[e] = add(
+            add(self.own(e), self.total_of(self.left[e])),
+            self.total_of(self.right[e]),
+        );
+    }
+
+    fn own(&self, e: usize) -> (i32, i32) {
+        (self.atomic[e].winding_a, self.atomic[e].winding_b)
+    }
+
+    fn total_of(&self, tree: Option<usize>) -> (i32, i32) {
+        tree.map_or((0, 0), |t| self.total[t])
+    }
+
+    fn lo(&self, e: usize) -> Point<T> {
+        self.nodes[self.atomic[e].lo]
+    }
+
+    fn hi(&self, e: usize) -> Point<T> {
+        self.nodes[self.atomic[e].hi]
+    }
+}
+
+/// Is the edge c -> d to the left or the right of the upwards edge a -> b, when it doesn't cross it.
+fn side_of<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>, d: Point<T>) -> Ordering {
+    // if the edges share an end point the other end decides it
+    let side = match geom::orientation(a, b, c) {
+        Orientation::Collinear => geom::orientation(a, b, d),
+        side => side,
+    };
+
+    match side {
+        Orientation::AntiClockwise => Ordering::Less,
+        Orientation::Clockwise => Ordering::Greater,
+        Orientation::Collinear => Ordering::Equal,
+    }
+}
+
+fn add(a: (i32, i32), b: (i32, i32)) -> (i32, i32) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+/// A well mixed pseudo random priority for the treap in Spanning.
+fn priority(e: usize) -> u64 {
+    let mut x = (e as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Join the directed result edges into closed rings.
+///
+/// Where several edges leave the same point we take the sharpest turn, which keeps rings that only touch at a
+/// point separate from each other. A ring that still comes back through a point it has already been to is cut
+/// there, so the part that is pinched off becomes a ring of its own. Edges that can't be joined up into a closed ring
+/// are left out, in which case the second value is false.
+fn link_rings<T: Coordinate>(
+    nodes: &[Point<T>],
+    edges: &[(usize, usize)],
+) -> (Vec<Vec<Point<T>>>, bool) {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, (from, _)) in edges.iter().enumerate() {
+        outgoing.entry(*from).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    let mut complete = true;
+
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+
+        let mut ring = Vec::new();
+        let mut current = start;
+        let closed = loop {
+            used[current] = true;
+            let (from, to) = edges[current];
+            ring.push(from);
+
+            let next = outgoing.get(&to).and_then(|out| {
+                out.iter()
+                    .filter(|e| **e == start || !used[**e])
+                    .min_by(|e, f| {
+                        T::cmp_turn(
+                            nodes[to],
+                            nodes[from],
+                            nodes[edges[**e].1],
+                            nodes[edges[**f].1],
+                        )
+                    })
+            });
+
+            match next {
+                Some(e) if *e == start => break true,
+                Some(e) => current = *e,
+                None => break false,
+            }
+        };
+
+        if closed {
+            rings.extend(cut_at_repeats(ring));
+        } else {
+            complete = false;
+        }
+    }
+
+    // a point where rings touch has to stay in all of them, even if it is in the middle of a straight line in one
+    // of them, or the other ring would end up touching the middle of a side.
+    let mut uses = vec![0; nodes.len()];
+    for node in rings.iter().flatten() {
+        uses[*node] += 1;
+    }
+
+    let result = rings
+        .into_iter()
+        .map(|ring| simplify(nodes, ring, &uses))
+        .filter(|ring| ring.len() >= 3)
+        .collect();
+
+    (result, complete)
+}
+
+/// Split a ring of nodes wherever it comes back to a node it has already been through, so that none of the rings
+/// returned go through the same node twice.
+fn cut_at_repeats(ring: Vec<usize>) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut path: Vec<usize> = Vec::new();
+    let mut position: HashMap<usize, usize> = HashMap::new();
+
+    for node in ring {
+        if let Some(&at) = position.get(&node) {
+            // the path since the last visit is a closed loop of its own.
+            let pinched = path.split_off(at);
+            for n in pinched.iter() {
+                position.remove(n);
+            }
+            result.push(pinched);
+        }
+        position.insert(node, path.len());
+        path.push(node);
+    }
+    result.push(path);
+
+    // two edges going there and straight back enclose nothing
+    result.retain(|ring| ring.len() >= 3);
+    result
+}
+
+/// The counter clockwise angle from the direction we came in from to an outgoing direction.
+fn turn(back: f64, out: f64) -> f64 {
+    let angle = (out - back).rem_euclid(TAU);
+    if angle == 0.0 {
+        TAU
+    } else {
+        angle
+    }
+}
+
+/// The coordinate types the overlay can work with, and the few calculations that have to be done differently for them.
+pub trait Coordinate: Scalar {
+    /// How close together points have to be to be treated as the same node, given the largest coordinate.
+    fn merge_distance(largest: f64) -> f64;
+
+    /// The cell of a grid of the given size the point is in. Points within the distance of each other are always in
+    /// the same or neighbouring cells.
+    fn cell(p: Point<Self>, size: f64) -> (i64, i64);
+
+    /// Are the two points within the distance of each other along both axes.
+    fn near(p: Point<Self>, q: Point<Self>, distance: f64) -> bool;
+
+    /// The point where a -> b properly crosses c -> d.
+    fn crossing_point(
+        a: Point<Self>,
+        b: Point<Self>,
+        c: Point<Self>,
+        d: Point<Self>,
+    ) -> Point<Self>;
+
+    /// Compare how far along the line a -> b the points p and q are.
+    fn cmp_along(a: Point<Self>, b: Point<Self>, p: Point<Self>, q: Point<Self>) -> Ordering;
+
+    fn midpoint(a: Point<Self>, b: Point<Self>) -> Point<Self>;
+
+    /// Arriving at `at` from `from`, compare how far counter clockwise we have to turn to head to p or to q.
+    /// Heading straight back to `from` counts as a full turn.
+    fn cmp_turn(at: Point<Self>, from: Point<Self>, p: Point<Self>, q: Point<Self>) -> Ordering;
+}
+
+impl Coordinate for f64 {
+    fn merge_distance(largest: f64) -> f64 {
+        // a few thousand times the rounding error of the crossing points
+        largest * 2.0_f64.powi(-40)
+    }
+
+    fn cell(p: Point, size: f64) -> (i64, i64) {
+        if size > 0.0 {
+            ((p.x / size).floor() as i64, (p.y / size).floor() as i64)
+        } else {
+            // adding zero turns -0.0 into 0.0 so they end up in the same cell
+            ((p.x + 0.0).to_bits() as i64, (p.y + 0.0).to_bits() as i64)
+        }
+    }
+
+    fn near(p: Point, q: Point, distance: f64) -> bool {
+        (p.x - q.x).abs() <= distance && (p.y - q.y).abs() <= distance
+    }
+
+    fn crossing_point(a: Point, b: Point, c: Point, d: Point) -> Point {
+        geom::crossing_point(a, b, c, d)
+    }
+
+    fn cmp_along(a: Point, b: Point, p: Point, q: Point) -> Ordering {
+        let length = (b.x - a.x) * (b.x - a.x) + (b.y - a.y) * (b.y - a.y);
+        let param = |p: Point| ((p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y)) / length;
+        param(p).total_cmp(&param(q))
+    }
+
+    fn midpoint(a: Point, b: Point) -> Point {
+        Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)
+    }
+
+    fn cmp_turn(at: Point, from: Point, p: Point, q: Point) -> Ordering {
+        let back = at.angle_to(&from);
+        turn(back, at.angle_to(&p)).total_cmp(&turn(back, at.angle_to(&q)))
+    }
+}
+
+/// Integer coordinates are worked out exactly with 128 bit maths.
+///
+/// All the coordinates must be even and no bigger than 2^61, so that the midpoint of every edge is also a whole
+/// number and none of the products overflow. Crossing points are rounded to the nearest even coordinates.
+impl Coordinate for i64 {
+    fn merge_distance(_largest: f64) -> f64 {
+        // the maths is exact, so only the same point is the same node
+        0.0
+    }
+
+    fn cell(p: Point<i64>, _size: f64) -> (i64, i64) {
+        (p.x, p.y)
+    }
+
+    fn near(p: Point<i64>, q: Point<i64>, _distance: f64) -> bool {
+        same(p, q)
+    }
+
+    fn crossing_point(a: Point<i64>, b: Point<i64>, c: Point<i64>, d: Point<i64>) -> Point<i64> {
+        let [a, b, c, d] = [a, b, c, d].map(wide);
+        let t_top = cross_wide(offset(c, a), offset(d, c));
+        let t_bottom = cross_wide(offset(b, a), offset(d, c));
+
+        // a + t * (b - a), rounded to the grid of even numbers. As the lines properly cross 0 < t < 1.
+        let along = |from: i128, delta: i128| from + 2 * mul_div_round(delta, t_top, 2 * t_bottom);
+        Point::from_xy(along(a.x, b.x - a.x) as i64, along(a.y, b.y - a.y) as i64)
+    }
+
+    fn cmp_along(a: Point<i64>, b: Point<i64>, p: Point<i64>, q: Point<i64>) -> Ordering {
+        let [a, b, p, q] = [a, b, p, q].map(wide);
+        dot_wide(offset(p, a), offset(b, a)).cmp(&dot_wide(offset(q, a), offset(b, a)))
+    }
+
+    fn midpoint(a: Point<i64>, b: Point<i64>) -> Point<i64> {
+        Point::from_xy(a.x / 2 + b.x / 2, a.y / 2 + b.y / 2)
+    }
+
+    fn cmp_turn(at: Point<i64>, from: Point<i64>, p: Point<i64>, q: Point<i64>) -> Ordering {
+        let [at, from, p, q] = [at, from, p, q].map(wide);
+        let back = offset(from, at);
+
+        // split the turns into the first half, up to and including heading straight on, and the second half which
+        // ends with going back the way we came. Within each half the cross product puts them in order.
+        let half = |v: Point<i128>| {
+            let side = cross_wide(back, v);
+            if side > 0 || (side == 0 && dot_wide(back, v) < 0) {
+                0
+            } else {
+                1
+            }
+        };
+        let (p, q) = (offset(p, at), offset(q, at));
+
+        half(p).cmp(&half(q)).then_with(|| 0.cmp(&cross_wide(p, q)))
+    }
+}
+
+fn wide(p: Point<i64>) -> Point<i128> {
+    Point {
+        x: p.x as i128,
+        y: p.y as i128,
+    }
+}
+
+/// a - b
+fn offset(a: Point<i128>, b: Point<i128>) -> Point<i128> {
+    Point {
+        x: a.x - b.x,
+        y: a.y - b.y,
+    }
+}
+
+fn cross_wide(a: Point<i128>, b: Point<i128>) -> i128 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot_wide(a: Point<i128>, b: Point<i128>) -> i128 {
+    a.x * b.x + a.y * b.y
+}
+
+/// a * b / c rounded to the nearest whole number, with halves rounded away from zero.
+///
+/// a * b can be far bigger than an i128 so it is worked out as a 256 bit number, the answer has to fit in an i128.
+fn mul_div_round(a: i128, b: i128, c: i128) -> i128 {
+    let negative = (a < 0) ^ (b < 0) ^ (c < 0);
+    let (a, b, c) = (a.unsigned_abs(), b.unsigned_abs(), c.unsigned_abs());
+
+    // multiply as four 64 bit halves
+    let mask = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & mask);
+    let (b_high, b_low) = (b >> 64, b & mask);
+    let low = a_low * b_low;
+    let middle1 = a_high * b_low;
+    let middle2 = a_low * b_high;
+    let (middle, middle_carry) = middle1.overflowing_add(middle2);
+    let (low, low_carry) = low.overflowing_add(middle << 64);
+    let high =
+        a_high * b_high + (middle >> 64) + ((middle_carry as u128) << 64) + low_carry as u128;
+
+    // long division one bit at a time. c is at most 2^127 so the remainder can be doubled without overflowing.
+    let mut quotient: u128 = 0;
+    let mut remainder: u128 = 0;
+    for i in (0..256).rev() {
+        let bit = if i >= 128 {
+            (high >> (i - 128)) & 1
+        } else {
+            (low >> i) & 1
+        };
+        remainder = (remainder << 1) | bit;
+        quotient <<= 1;
+        if remainder >= c {
+            remainder -= c;
+            quotient |= 1;
+        }
+    }
+
+    if remainder >= c - remainder {
+        quotient += 1;
+    }
+
+    if negative {
+        -(quotient as i128)
+    } else {
+        quotient as i128
+    }
+}
+
+/// Remove points in the middle of straight lines and start the ring from its lowest, left most point.
+///
+/// Points used more than once across all the rings are kept, as another ring touches this one there.
+fn simplify<T: Scalar>(nodes: &[Point<T>], mut ring: Vec<usize>, uses: &[usize]) -> Vec<Point<T>> {
+    let mut i = 0;
+    while ring.len() >= 3 && i < ring.len() {
+        let prev = nodes[ring[(i + ring.len() - 1) % ring.len()]];
+        let next = nodes[ring[(i + 1) % ring.len()]];
+        if uses[ring[i]] == 1
+            && geom::orientation(prev, nodes[ring[i]], next) == Orientation::Collinear
+        {
+            ring.remove(i);
+            // the previous point might now be in the middle of a straight line.
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+
+    let mut ring: Vec<Point<T>> = ring.into_iter().map(|n| nodes[n]).collect();
+    if let Some(start) = (0..ring.len()).min_by(|i, j| {
+        ring[*i]
+            .x
+            .total_cmp(&ring[*j].x)
+            .then(ring[*i].y.total_cmp(&ring[*j].y))
+    }) {
+        ring.rotate_left(start);
+    }
+
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cut_at_repeats, is_simple, link_rings, try_overlay, Operation};
+    use crate::{point::Point, polygon::FillRule};
+
+    #[test]
+    fn link_rings_leaves_out_open_chains() {
+        let nodes = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(5.0, 5.0),
+            Point::new(6.0, 5.0),
+        ];
+        let closed = [(0, 1), (1, 2), (2, 0)];
+
+        assert!(link_rings(&nodes, &closed).1);
+
+        let (rings, complete) = link_rings(&nodes, &[closed[0], closed[1], closed[2], (3, 4)]);
+        assert!(!complete);
+        assert_eq!(rings, link_rings(&nodes, &closed).0);
+    }
+
+    #[test]
+    fn cut_at_repeats_pinched_rings() {
+        assert_eq!(
+            cut_at_repeats(vec![0, 1, 2, 3, 4, 2, 5]),
+            vec![vec![2, 3, 4], vec![0, 1, 2, 5]]
+        );
+        // a spike out and back encloses nothing
+        assert_eq!(cut_at_repeats(vec![0, 1, 2, 3, 2]), vec![vec![0, 1, 2]]);
+        assert_eq!(cut_at_repeats(vec![0, 1, 2]), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn is_simple_rings() {
+        let ring =
+            |points: &[(f64, f64)]| points.iter().copied().map(Point::from).collect::<Vec<_>>();
+
+        assert!(is_simple(&ring(&[
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.0)
+        ])));
+        assert!(is_simple(&ring(&[
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (0.0, 2.0),
+            (1.0, 0.0)
+        ])));
+        assert!(!is_simple(&ring(&[(0.0, 0.0), (0.0, 1.0)])));
+        // pinched at (1, 1)
+        assert!(!is_simple(&ring(&[
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+            (0.0, 2.0)
+        ])));
+        // (1, 0) is on the side from (0, 0) to (2, 0)
+        assert!(!is_simple(&ring(&[
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 0.0),
+            (0.0, 2.0)
+        ])));
+        // a spike folding back along the side before it, including at the start of the ring
+        assert!(!is_simple(&ring(&[
+            (0.0, 0.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+            (1.0, 0.0)
+        ])));
+        assert!(!is_simple(&ring(&[
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 2.0)
+        ])));
+    }
+
+    #[test]
+    fn try_overlay_crossings_rounded_apart() {
+        let a = [
+            (0.0, 2.0),
+            (1.0, 1.0),
+            (4.0, 1.0),
+            (3.0, 3.0),
+            (2.0, 1.0),
+            (4.0, 0.0),
+        ]
+        .map(Point::from);
+        let b = [(2.0, 3.0), (2.0, 0.0), (3.0, 3.0), (4.0, 4.0), (4.0, 0.0)].map(Point::from);
+
+        for op in [
+            Operation::Union,
+            Operation::Intersection,
+            Operation::Difference,
+            Operation::SymmetricDifference,
+        ] {
+            assert!(try_overlay(&[&a], &[&b], op, FillRule::EvenOdd).is_ok());
+        }
+    }
+}
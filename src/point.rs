@@ -1,12 +1,19 @@
 //!Point types and helpers around them
 use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use float_cmp::approx_eq;
+use crate::{
+    scalar::{self, Scalar},
+    tolerance::Tolerance,
+};
 
+/// A point in 2d space.
+///
+/// The coordinates are f64 unless another [`Scalar`] type is given, for example `Point<i64>` for grid coordinates.
 #[derive(Debug, Clone, Copy)]
-pub struct Point {
-    pub x: f64,
-    pub y: f64,
+pub struct Point<T = f64> {
+    pub x: T,
+    pub y: T,
 }
 
 impl Point {
@@ -43,77 +50,231 @@ impl Point {
         }
     }
 
+    /// Return the angle in radians to another point
+    pub fn angle_to(&self, other: &Point) -> f64 {
+        let translated = *other - *self;
+
+        let result = translated.y.atan2(translated.x);
+        if result < 0.0 {
+            return result + 360.0_f64.to_radians();
+        }
+        result
+    }
+
+    /// Rotate the given point around the origin by angle radians.
+    pub fn rotate(&self, angle: f64) -> Point {
+        Point {
+            x: (self.x * angle.cos()) - (self.y * angle.sin()),
+            y: (self.y * angle.cos()) + (self.x * angle.sin()),
+        }
+    }
+
+    /// Scale the given point away from the origin, multiplying x by sx and y by sy.
+    pub fn scale(&self, sx: f64, sy: f64) -> Point {
+        Point {
+            x: self.x * sx,
+            y: self.y * sy,
+        }
+    }
+
+    /// The dot product of this and another point, treating both as vectors from the origin.
+    pub fn dot(&self, other: &Point) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The 2d cross product of this and another point, treating both as vectors from the origin.
+    ///
+    /// Positive if other is counter clockwise from this, negative if it is clockwise and zero if they are parallel.
+    pub fn cross(&self, other: &Point) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// The distance from the origin to this point.
+    pub fn length(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// The distance between this and another point.
+    pub fn distance_to(&self, other: &Point) -> f64 {
+        (*other - *self).length()
+    }
+
+    /// Scale this point so it is 1 away from the origin, in the same direction.
+    /// The origin has no direction so it is returned unchanged.
+    pub fn normalize(&self) -> Point {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+        *self / length
+    }
+
+    /// Interpolate between this point, when t is 0, and the other point, when t is 1.
+    pub fn lerp(&self, other: &Point, t: f64) -> Point {
+        *self + (*other - *self) * t
+    }
+
+    /// Move this point to the nearest point on a grid with the tolerance's epsilon as the spacing.
+    pub fn snap(&self, tolerance: Tolerance) -> Point {
+        Point {
+            x: tolerance.snap(self.x),
+            y: tolerance.snap(self.y),
+        }
+    }
+}
+
+impl<T: Scalar> Point<T> {
+    /// Create a point with any type of coordinates. `Point::new` only creates f64 points.
+    pub fn from_xy(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
     /// Given another point return the min of the x and y values
-    pub fn min(&self, other: &Point) -> Point {
+    pub fn min(&self, other: &Point<T>) -> Point<T> {
         Point {
-            x: self.x.min(other.x),
-            y: self.y.min(other.y),
+            x: scalar::min(self.x, other.x),
+            y: scalar::min(self.y, other.y),
         }
     }
 
     /// Given another point return the max of the x and y values
-    pub fn max(&self, other: &Point) -> Point {
+    pub fn max(&self, other: &Point<T>) -> Point<T> {
         Point {
-            x: self.x.max(other.x),
-            y: self.y.max(other.y),
+            x: scalar::max(self.x, other.x),
+            y: scalar::max(self.y, other.y),
         }
     }
 
     /// Flip the sign of both x and y coords
-    pub fn invert(&self) -> Point {
+    pub fn invert(&self) -> Point<T> {
         Point {
             x: -self.x,
             y: -self.y,
         }
     }
 
+    /// Rotate this point 90 degrees counter clockwise around the origin.
+    pub fn perpendicular(&self) -> Point<T> {
+        Point {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
     /// offset / translate this point by another one.
-    pub fn translate(&self, by: &Point) -> Point {
+    pub fn translate(&self, by: &Point<T>) -> Point<T> {
         Point {
             x: self.x + by.x,
             y: self.y + by.y,
         }
     }
 
-    /// Return the angle in radians to another point
-    pub fn angle_to(&self, other: &Point) -> f64 {
-        let translated = other.translate(&self.invert());
-
-        let result = translated.y.atan2(translated.x);
-        if result < 0.0 {
-            return result + 360.0_f64.to_radians();
-        }
-        result
+    /// Returns true if both coordinates of the two points are equal within the tolerance.
+    pub fn approx_eq(&self, other: &Point<T>, tolerance: Tolerance) -> bool {
+        self.x.approx_eq(other.x, tolerance) && self.y.approx_eq(other.y, tolerance)
     }
 
-    /// Rotate the given point around the origin by angle radians.
-    pub fn rotate(&self, angle: f64) -> Point {
+    /// Convert this point to another coordinate type. Integer coordinates are rounded to the nearest whole number.
+    pub fn cast<U: Scalar>(&self) -> Point<U> {
         Point {
-            x: (self.x * angle.cos()) - (self.y * angle.sin()),
-            y: (self.y * angle.cos()) + (self.x * angle.sin()),
+            x: U::from_f64(self.x.to_f64()),
+            y: U::from_f64(self.y.to_f64()),
         }
     }
 }
 
-impl fmt::Display for Point {
+impl<T: fmt::Display> fmt::Display for Point<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "({}, {})", self.x, self.y)
     }
 }
 
-impl PartialEq for Point {
+impl<T: Scalar> PartialEq for Point<T> {
     // float equal is always evil, but we will use approx_eq here to give us a reasonable answer.
+    // Use approx_eq directly to choose a tolerance that suits your data.
     fn eq(&self, other: &Self) -> bool {
-        approx_eq!(f64, self.x, other.x, epsilon = 0.000003, ulps = 2)
-            && approx_eq!(f64, self.y, other.y, epsilon = 0.000003, ulps = 2)
+        self.approx_eq(other, Tolerance::default())
+    }
+}
+
+impl<T: Scalar> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        self.translate(&other)
+    }
+}
+
+impl<T: Scalar> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<T: Scalar> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        self.invert()
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f64) -> Point {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl MulAssign<f64> for Point {
+    fn mul_assign(&mut self, factor: f64) {
+        *self = *self * factor;
+    }
+}
+
+impl Div<f64> for Point {
+    type Output = Point;
+
+    fn div(self, divisor: f64) -> Point {
+        Point {
+            x: self.x / divisor,
+            y: self.y / divisor,
+        }
+    }
+}
+
+impl DivAssign<f64> for Point {
+    fn div_assign(&mut self, divisor: f64) {
+        *self = *self / divisor;
     }
 }
 
 /*
-Convert a float tuple into a point automagically
+Convert a tuple into a point automagically
 */
-impl From<(f64, f64)> for Point {
-    fn from(other: (f64, f64)) -> Point {
+impl<T: Scalar> From<(T, T)> for Point<T> {
+    fn from(other: (T, T)) -> Point<T> {
         Point {
             x: other.0,
             y: other.1,
@@ -133,12 +294,11 @@ impl From<(i32, i32)> for Point {
     }
 }
 
-// TODO: compare function for points thats fuzzy for the float matching...
-
 #[cfg(test)]
 mod tests {
 
     use crate::point::Point;
+    use crate::tolerance::Tolerance;
 
     use crate::tests::assert_f64;
 
@@ -191,4 +351,125 @@ mod tests {
 
         assert_eq!(result, p);
     }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let a = Point::new(1000.0, 2000.0);
+        let b = Point::new(1000.0001, 2000.0);
+
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, Tolerance::new(0.001, 2)));
+        assert!(!a.approx_eq(&Point::new(1000.0000001, 2000.0), Tolerance::exact()));
+    }
+
+    #[test]
+    fn snap() {
+        let p = Point::new(1.26, -3.74);
+
+        assert_eq!(p.snap(Tolerance::new(0.5, 0)), Point::new(1.5, -3.5));
+        assert_eq!(p.snap(Tolerance::exact()), p);
+    }
+
+    #[test]
+    fn other_coordinate_types() {
+        let p: Point<i64> = Point::from_xy(3, -4);
+        assert_eq!(p.translate(&Point::from_xy(1, 1)), Point::from_xy(4, -3));
+        assert_eq!(p.invert(), (-3, 4).into());
+
+        assert_eq!(p.cast::<f64>(), Point::new(3.0, -4.0));
+        assert_eq!(Point::new(2.5, -0.4).cast::<i32>(), Point::from_xy(3, 0));
+        assert_eq!(
+            Point::new(0.1, 0.2).cast::<f32>(),
+            Point::from_xy(0.1_f32, 0.2)
+        );
+    }
+
+    #[test]
+    fn operators() {
+        let mut p = Point::new(1.0, 2.0);
+        let q = Point::new(3.0, -1.0);
+
+        assert_eq!(p + q, Point::new(4.0, 1.0));
+        assert_eq!(p - q, Point::new(-2.0, 3.0));
+        assert_eq!(-p, Point::new(-1.0, -2.0));
+        assert_eq!(p * 2.0, Point::new(2.0, 4.0));
+        assert_eq!(p / 4.0, Point::new(0.25, 0.5));
+
+        p += q;
+        assert_eq!(p, Point::new(4.0, 1.0));
+        p -= q;
+        assert_eq!(p, Point::new(1.0, 2.0));
+        p *= 3.0;
+        assert_eq!(p, Point::new(3.0, 6.0));
+        p /= 3.0;
+        assert_eq!(p, Point::new(1.0, 2.0));
+
+        let i: Point<i32> = Point::from_xy(1, 2);
+        assert_eq!(
+            i + Point::from_xy(2, 2) - Point::from_xy(5, 0),
+            Point::from_xy(-2, 4)
+        );
+        assert_eq!(-i, Point::from_xy(-1, -2));
+    }
+
+    macro_rules! vector_tests {
+        ($($name:ident: $a:expr, $b:expr, $dot:expr, $cross:expr, $distance:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_f64!($a.dot(&$b), $dot);
+                    assert_f64!($a.cross(&$b), $cross);
+                    assert_f64!($a.distance_to(&$b), $distance);
+                    assert_f64!($b.distance_to(&$a), $distance);
+                }
+            )*
+        };
+    }
+
+    vector_tests!(
+        vector_same_direction: Point::new(1.0, 0.0), Point::new(3.0, 0.0), 3.0, 0.0, 2.0,
+        vector_right_angle: Point::new(3.0, 0.0), Point::new(0.0, 4.0), 0.0, 12.0, 5.0,
+        vector_clockwise: Point::new(0.0, 4.0), Point::new(3.0, 0.0), 0.0, -12.0, 5.0,
+        vector_opposite: Point::new(1.0, 1.0), Point::new(-2.0, -2.0), -4.0, 0.0, 18.0_f64.sqrt(),
+    );
+
+    #[test]
+    fn length_and_normalize() {
+        let p = Point::new(3.0, -4.0);
+
+        assert_f64!(p.length(), 5.0);
+        assert_eq!(p.normalize(), Point::new(0.6, -0.8));
+        assert_f64!(p.normalize().length(), 1.0);
+        assert_eq!(Point::zero().normalize(), Point::zero());
+    }
+
+    #[test]
+    fn lerp() {
+        let a = Point::new(1.0, 1.0);
+        let b = Point::new(3.0, 5.0);
+
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.25), Point::new(1.5, 2.0));
+        assert_eq!(a.lerp(&b, 2.0), Point::new(5.0, 9.0));
+    }
+
+    #[test]
+    fn perpendicular() {
+        let p = Point::new(2.0, 1.0);
+
+        assert_eq!(p.perpendicular(), Point::new(-1.0, 2.0));
+        assert_f64!(p.dot(&p.perpendicular()), 0.0);
+        assert!(p.cross(&p.perpendicular()) > 0.0);
+        assert_eq!(Point::from_xy(2, 1).perpendicular(), Point::from_xy(-1, 2));
+    }
+
+    #[test]
+    fn scale() {
+        let p = Point::new(2.0, -3.0);
+
+        assert_eq!(p.scale(2.0, 0.5), Point::new(4.0, -1.5));
+        assert_eq!(p.scale(-1.0, 1.0), Point::new(-2.0, -3.0));
+        assert_eq!(p.scale(0.0, 0.0), Point::zero());
+    }
 }
@@ -1,36 +1,124 @@
-use float_cmp::approx_eq;
-
-use crate::{boundingbox::BoundingBox, geom, point::Point};
+use crate::{
+    boundingbox::BoundingBox,
+    error::{self, Error},
+    geom,
+    multipolygon::MultiPolygon,
+    overlay::{self, Operation},
+    point::Point,
+    scalar::Scalar,
+    sweep,
+    tolerance::Tolerance,
+};
 use std::{
     fmt::{self, Display},
     iter::zip,
-    mem,
+    ops::ControlFlow,
 };
 
+/// The direction the points of a polygon go around it in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// The polygon has no area, so it doesn't go either way around.
+    Degenerate,
+}
+
+/// Where a point is relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Outside,
+    OnBoundary(Boundary),
+}
+
+/// Which part of the boundary of a polygon a point is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// The point is exactly on the corner with this index in the polygon's points.
+    Vertex(usize),
+    /// The point is on the side with this index, as returned by `get_side`, but not on either end of it.
+    Edge(usize),
+}
+
+/// How to decide which parts of a self intersecting polygon are inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside if a line from it to infinity crosses the edges an odd number of times.
+    /// Areas where the polygon overlaps itself are treated as outside.
+    EvenOdd,
+    /// A point is inside if the edges wind around it at least once, in either direction.
+    /// Areas where the polygon overlaps itself are treated as inside.
+    NonZero,
+}
+
+impl FillRule {
+    /// Is a point with this winding number inside under this rule
+    pub fn fills(&self, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => winding.rem_euclid(2) == 1,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+/// A place where two sides of a polygon cross each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfIntersection {
+    /// The index of the first side, as returned by `get_side`.
+    pub edge_a: usize,
+    /// The index of the second side, always greater than `edge_a`.
+    pub edge_b: usize,
+    /// Where the two sides cross.
+    pub point: Point,
+}
+
 /// Polygon describes a the points around the edge of a shape. It can only contain and single path, no holes
+/// See [`PolygonWithHoles`](crate::polygonwithholes::PolygonWithHoles) for shapes with holes in them.
+///
+/// The coordinates are f64 unless another [`Scalar`] type is given. Which operations are available depends on it:
+///
+/// - Any of `f32`, `f64`, `i32` and `i64`: `contains`, `locate`, `winding_number`, `intersects`,
+///   `is_self_intersecting`, `sides`, `translate`, `approx_eq` and `cast`.
+/// - `f64` only: everything that measures the shape or creates new points. That is the areas, `orientation`,
+///   `center`, `self_intersections`, `make_valid`, the rotations and scales, `snap`, `to_integer` and the boolean
+///   operations.
+/// - `i64` only: the exact boolean operations, `doubled_signed_area` and `to_float`.
+///
+/// `f32` and `i32` polygons have no areas, rotations or boolean operations of their own, use `cast` to convert them
+/// to `f64` or `i64` first.
 #[allow(clippy::len_without_is_empty)] // a polygon can never be empty so an is_empty function would always return false.
 #[derive(Debug, Clone)]
-pub struct Polygon {
-    pub points: Vec<Point>,
-    pub bounds: BoundingBox,
+pub struct Polygon<T = f64> {
+    pub points: Vec<Point<T>>,
+    pub bounds: BoundingBox<T>,
 }
 
-impl Polygon {
+impl<T: Scalar> Polygon<T> {
     /// Create a new polygon.
     ///
-    /// The vector of points must contain at least 3 elements or this will panic.
-    pub fn new(points: Vec<Point>) -> Self {
+    /// The vector of points must contain at least 3 elements or this will panic. Use `try_new` to get an error instead.
+    pub fn new(points: Vec<Point<T>>) -> Self {
         if points.len() < 3 {
-            panic!(
-                "Trying to create a polygon with {} points. You need at least 3",
-                points.len()
-            )
+            panic!("{}", Error::TooFewPoints(points.len()))
         }
 
         let bounds = BoundingBox::from_points(&points);
         Polygon { points, bounds }
     }
 
+    /// Create a new polygon, returning an error rather than panicking if the points can't make a polygon.
+    ///
+    /// There must be at least 3 points and they must all have finite coordinates.
+    pub fn try_new(points: Vec<Point<T>>) -> Result<Self, Error> {
+        if points.len() < 3 {
+            return Err(Error::TooFewPoints(points.len()));
+        }
+        error::check_finite(&points)?;
+
+        Ok(Polygon::new(points))
+    }
+
     // TODO: circles
     // TODO: rectangle
 
@@ -40,10 +128,10 @@ impl Polygon {
     }
 
     /// Return the two points describing a side of this polygon. Indexing from zero.
-    pub fn get_side(&self, i: usize) -> (Point, Point) {
+    pub fn get_side(&self, i: usize) -> (Point<T>, Point<T>) {
         let p1 = self.points[i];
         // handle that the polygon wraps around back to the start.
-        let p2: Point = if i + 1 >= self.points.len() {
+        let p2: Point<T> = if i + 1 >= self.points.len() {
             self.points[0]
         } else {
             self.points[i + 1]
@@ -53,11 +141,11 @@ impl Polygon {
     }
 
     /// Return a vector of point pairs for every side of this polygon, in order.
-    pub fn sides(&self) -> Vec<(Point, Point)> {
+    pub fn sides(&self) -> Vec<(Point<T>, Point<T>)> {
         self.sides_from(0)
     }
 
-    fn sides_from(&self, start: usize) -> Vec<(Point, Point)> {
+    fn sides_from(&self, start: usize) -> Vec<(Point<T>, Point<T>)> {
         let mut result = Vec::new();
 
         // include the first half of the list
@@ -73,40 +161,155 @@ impl Polygon {
         result
     }
 
-    /// Do any of the lines of this polygon cross over any other lines?
+    /// Do any of the lines of this polygon cross over any other lines, or fold back along the line next to them?
+    ///
+    /// The sides are compared with a sweep line, which takes O(n log² n + k) time where k is the number of pairs of
+    /// sides whose bounding boxes overlap.
     pub fn is_self_intersecting(&self) -> bool {
-        for i in 0..self.points.len() {
-            let (p1, p2) = self.get_side(i);
-            for j in i + 1..self.points.len() {
-                let (p3, p4) = self.get_side(j);
-                // if the two lines share a point then skip this as yes they intersect according to the
-                // geom function but not as far as this is concerned.
-                if p1 == p3 || p1 == p4 || p2 == p3 || p2 == p4 {
-                    continue;
-                }
-                if geom::lines_intersect(p1, p2, p3, p4) {
-                    return true;
-                }
+        let sides = self.sides();
+        sweep::intersecting_pairs(&sides, |i, j| {
+            let (p1, p2) = sides[i];
+            let (p3, p4) = sides[j];
+            // if the two lines share a point then skip this as yes they intersect according to the
+            // geom function but not as far as this is concerned, unless they fold back along each other.
+            match geom::shared_end(p1, p2, p3, p4) {
+                Some((s, p, q)) if geom::fold_point(s, p, q).is_none() => ControlFlow::Continue(()),
+                _ => ControlFlow::Break(()),
+            }
+        })
+        .is_break()
+    }
+}
+
+impl Polygon {
+    /// Find every place where the sides of this polygon cross each other.
+    ///
+    /// Sides that share a point, such as neighbouring sides, are not reported as crossing each other unless they fold
+    /// back along each other like the two sides of a spike. Then the tip of the shorter one is reported.
+    /// If two sides overlap along their length one point where they touch is reported.
+    pub fn self_intersections(&self) -> Vec<SelfIntersection> {
+        let sides = self.sides();
+        let mut pairs = Vec::new();
+        let _ = sweep::intersecting_pairs(&sides, |i, j| {
+            pairs.push((i, j));
+            ControlFlow::Continue(())
+        });
+        pairs.sort();
+
+        let mut result = Vec::new();
+        for (i, j) in pairs {
+            let (p1, p2) = sides[i];
+            let (p3, p4) = sides[j];
+            let point = match geom::shared_end(p1, p2, p3, p4) {
+                Some((s, p, q)) => geom::fold_point(s, p, q),
+                None => geom::point_of_intersection(p1, p2, p3, p4)
+                    .or_else(|| geom::touching_point(p1, p2, p3, p4)),
+            };
+            if let Some(point) = point {
+                result.push(SelfIntersection {
+                    edge_a: i,
+                    edge_b: j,
+                    point,
+                });
             }
         }
 
-        false
+        result
+    }
+
+    /// Repair this polygon into a set of simple polygons that don't cross themselves.
+    ///
+    /// Self intersecting parts are split into separate pieces, repeated points and zero area spikes are removed.
+    /// Rings in the result can touch each other at a point but none of them touch or cross themselves.
+    /// Uses the non zero fill rule, the same as `contains`, so the result covers the same points.
+    pub fn make_valid(&self) -> MultiPolygon {
+        self.make_valid_with_rule(FillRule::NonZero)
+    }
+
+    /// Repair this polygon into a set of simple polygons, using the fill rule to decide what is inside it.
+    pub fn make_valid_with_rule(&self, rule: FillRule) -> MultiPolygon {
+        MultiPolygon::from_rings(overlay::overlay(
+            &[&self.points],
+            &[],
+            Operation::Union,
+            rule,
+        ))
+    }
+
+    /// Return the signed area of this polygon.
+    ///
+    /// This is positive if the points go counter clockwise around the polygon and negative if they go clockwise.
+    /// The result is meaningless for self intersecting polygons.
+    pub fn signed_area(&self) -> f64 {
+        geom::signed_area(&self.points)
     }
 
-    /// Return the area of this polygon
-    /// Note: This will panic if the polygon is self intersecting.
+    /// Return the area of this polygon. This is always positive, whichever way around the points go.
+    /// Note: This will panic if the polygon is self intersecting, `try_area` will return an error instead.
+    /// `area_with_rule` works for self intersecting polygons.
     pub fn area(&self) -> f64 {
+        match self.try_area() {
+            Ok(area) => area,
+            Err(e) => panic!("Can not calculate the area: {}", e),
+        }
+    }
+
+    /// Return the area of this polygon, or an error if the polygon is self intersecting.
+    pub fn try_area(&self) -> Result<f64, Error> {
         if self.is_self_intersecting() {
-            panic!("Can not calculate the area of a self intersecting polygon")
+            return Err(Error::SelfIntersecting);
         }
 
-        let sides = self.sides();
-        let triangle_sum = sides
-            .iter()
-            .map(|s| geom::area_of_triangle(Point::zero(), s.0, s.1))
-            .sum();
+        Ok(self.signed_area().abs())
+    }
+
+    /// Return which direction the points of this polygon go around it in, based on the sign of its area.
+    pub fn orientation(&self) -> Orientation {
+        let area = self.signed_area();
+        if area > 0.0 {
+            Orientation::CounterClockwise
+        } else if area < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::Degenerate
+        }
+    }
+
+    /// Create a copy of this polygon with the points going around it in the opposite direction.
+    /// The first point stays the same.
+    pub fn reversed(&self) -> Polygon {
+        let mut points = self.points.clone();
+        points[1..].reverse();
+        Polygon::new(points)
+    }
+
+    /// Create a copy of this polygon with the points going around it clockwise.
+    pub fn to_clockwise(&self) -> Polygon {
+        match self.orientation() {
+            Orientation::CounterClockwise => self.reversed(),
+            _ => self.clone(),
+        }
+    }
 
-        triangle_sum
+    /// Create a copy of this polygon with the points going around it counter clockwise.
+    pub fn to_counter_clockwise(&self) -> Polygon {
+        match self.orientation() {
+            Orientation::Clockwise => self.reversed(),
+            _ => self.clone(),
+        }
+    }
+
+    /// Return the area covered by this polygon, using the fill rule to decide what is inside it.
+    ///
+    /// Unlike `area` this works for self intersecting polygons, and never panics. Areas covered more than once are
+    /// only counted once.
+    pub fn area_with_rule(&self, rule: FillRule) -> f64 {
+        // the repaired rings run clockwise around the filled area, so their signed areas add up to the area without
+        // having to sort them into polygons and holes or check each of them.
+        -overlay::overlay(&[&self.points], &[], Operation::Union, rule)
+            .iter()
+            .map(|ring| geom::signed_area(ring))
+            .sum::<f64>()
     }
 
     /// Return the point average of this polygon giving a possible centre
@@ -122,40 +325,74 @@ impl Polygon {
 
         Point::new(x / len, y / len)
     }
+}
+
+impl<T: Scalar> Polygon<T> {
+    /// Contains returns true if the point p is inside of this polygon.
+    ///
+    /// Points on the edges or corners of the polygon count as inside.
+    /// If the polygon is self intersecting this uses the non zero fill rule.
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.contains_with_rule(p, FillRule::NonZero)
+    }
 
-    /// Contains returns true if the point p is inside of this polygon
-    pub fn contains(&self, p: Point) -> bool {
+    /// Contains returns true if the point p is inside of this polygon, using the fill rule to decide what is inside
+    /// a self intersecting polygon.
+    ///
+    /// Points on the edges or corners of the polygon count as inside.
+    pub fn contains_with_rule(&self, p: Point<T>, rule: FillRule) -> bool {
         // fast path check with the bounding box first, if its outside that then it can never be inside the polygon.
         if !self.bounds.contains(p) {
             return false;
         }
 
-        // work out the sum of the angles between adjacent points and the point we are checking.
-        // if the sum is equal to 360 degrees then we are inside the polygon.
-        let mut total = 0.0;
+        match geom::winding_number(&self.points, p) {
+            Some(winding) => rule.fills(winding),
+            None => true,
+        }
+    }
 
-        for i in 0..self.points.len() {
-            let (p1, p2) = self.get_side(i);
-            let angle_a = p.angle_to(&p2);
-            let angle_b = p.angle_to(&p1);
+    /// Work out if the point p is inside, outside or on the boundary of this polygon.
+    ///
+    /// Points that are exactly on a corner of the polygon are reported as on that vertex,
+    /// otherwise points exactly on a side are reported as on that edge.
+    pub fn locate(&self, p: Point<T>) -> Location {
+        if !self.bounds.contains(p) {
+            return Location::Outside;
+        }
 
-            // handle rolling around over the 360/0 degree line reasonably
-            let result = if angle_a > angle_b {
-                -((360.0_f64.to_radians() - angle_a) + angle_b)
-            } else {
-                angle_a - angle_b
-            };
+        for i in 0..self.len() {
+            let (a, b) = self.get_side(i);
+            if a.x == p.x && a.y == p.y {
+                return Location::OnBoundary(Boundary::Vertex(i));
+            }
+            if b.x == p.x && b.y == p.y {
+                return Location::OnBoundary(Boundary::Vertex((i + 1) % self.len()));
+            }
+            if geom::strictly_on_segment(a, b, p) {
+                return Location::OnBoundary(Boundary::Edge(i));
+            }
+        }
 
-            total += result;
+        match geom::winding_number(&self.points, p) {
+            Some(0) => Location::Outside,
+            _ => Location::Inside,
         }
-        approx_eq!(f64, total.abs(), 360.0_f64.to_radians(), ulps = 2)
+    }
+
+    /// Return the number of times the edges of this polygon wind around the point p.
+    ///
+    /// Counter clockwise loops count +1 and clockwise ones -1, so a point inside a simple clockwise polygon gives -1.
+    /// Points outside the polygon give 0. If p is on the boundary of the polygon, where the winding number is not
+    /// defined, this returns None.
+    pub fn winding_number(&self, p: Point<T>) -> Option<i32> {
+        geom::winding_number(&self.points, p)
     }
 
     /// Returns true if any part of the other polygon overlaps this one.
     /// Entirely containing other or being contained by other counts here.
-    /// Note: this has worst case runtime on two polygons that don't intersect but their bounding boxes do.
-    /// O(n^2) where n is the sum of the number of sides in the two polygons
-    pub fn intersects(&self, other: &Polygon) -> bool {
+    /// The sides are compared with a sweep line so only sides whose bounding boxes overlap are checked.
+    pub fn intersects(&self, other: &Polygon<T>) -> bool {
         // first check if the bounding boxes intersect as a quicker check
         if !self.bounds.intersects(&other.bounds) {
             return false;
@@ -163,15 +400,8 @@ impl Polygon {
 
         // if any other sides intersect then the two polygons intersect
         // This also checks any of the points being the same due to the way the lines_intersect algorithm works
-        let self_sides = self.sides();
-        let other_sides = other.sides();
-
-        for self_side in self_sides.iter() {
-            for other_side in other_sides.iter() {
-                if geom::lines_intersect(self_side.0, self_side.1, other_side.0, other_side.1) {
-                    return true;
-                }
-            }
+        if sweep::any_intersect(&self.sides(), &other.sides()) {
+            return true;
         }
 
         // If that wasn't true check if the first point of the other is inside this polygon.
@@ -190,7 +420,7 @@ impl Polygon {
     }
 
     /// Move this polygon by point p
-    pub fn translate(&self, p: Point) -> Polygon {
+    pub fn translate(&self, p: Point<T>) -> Polygon<T> {
         let points = self
             .points
             .iter()
@@ -199,6 +429,23 @@ impl Polygon {
         Polygon::new(points)
     }
 
+    /// Returns true if both polygons have the same points in the same order, equal within the tolerance.
+    pub fn approx_eq(&self, other: &Polygon<T>, tolerance: Tolerance) -> bool {
+        // if all the points in both polygons are equal then they are equal
+        if other.len() != self.len() {
+            return false;
+        }
+
+        zip(self.points.iter(), other.points.iter()).all(|(a, b)| a.approx_eq(b, tolerance))
+    }
+
+    /// Convert this polygon to another coordinate type. Integer coordinates are rounded to the nearest whole number.
+    pub fn cast<U: Scalar>(&self) -> Polygon<U> {
+        Polygon::new(self.points.iter().map(|p| p.cast()).collect())
+    }
+}
+
+impl Polygon {
     /// Rotate a polygon counter clockwise around its center point by angle radians
     pub fn rotate_around_center(&self, angle: f64) -> Polygon {
         let center = self.center();
@@ -220,80 +467,320 @@ impl Polygon {
         Polygon::new(new_points)
     }
 
-    /// Create a new polygon that is the union of this polygon and the other polygon provided.
-    pub fn union(&self, other: &Polygon) -> Polygon {
-        let mut result_points = Vec::new();
-        result_points.push(self.points[0]);
-        let mut current = self;
-        let mut not_current = other;
-
-        let mut current_index = 0;
-        let mut other_index = 0;
-        while current_index < current.len() {
-            // get a side
-
-            let current_side = current.get_side(current_index);
-            // look for an intersecting side in the other one.
-            let not_current_sides = not_current.sides_from(other_index);
-            let intersects_with = geom::line_intersects_others(current_side, &not_current_sides);
-            if let Some(oi) = intersects_with {
-                let other_line = not_current_sides[oi];
-
-                // Find the point of intersection (we can be pretty sure this intersects as we checked just now)
-                let point = geom::point_of_intersection(
-                    current_side.0,
-                    current_side.1,
-                    other_line.0,
-                    other_line.1,
-                )
-                .unwrap();
-
-                // add that point to the list
-                result_points.push(point);
-                // add the end of the intersecting line to the list, a two straight lines cant intersect twice.
-                // At least not in this simple flat plain universe.
-                result_points.push(other_line.1);
-
-                // swap current and other
-                mem::swap(&mut current, &mut not_current);
-
-                // set other_index to current_index, don't add one because this might cross back over this line again
-                other_index = current_index;
-                // set current_index to intersects_with
-                let mut target_index = other_index + oi;
-                if target_index > not_current.len() {
-                    target_index -= not_current.len();
-                }
-                current_index = target_index;
-            } else {
-                // Nothing intersects with this side so we can add the new end to the result list.
-                result_points.push(current_side.1);
-                current_index += 1;
+    /// Scale a polygon away from the anchor point, multiplying distances along x by sx and along y by sy.
+    ///
+    /// Negative scales mirror the polygon. If that would flip the polygon over the points are reversed so it keeps
+    /// going the same way around.
+    pub fn scale_around(&self, anchor: Point, sx: f64, sy: f64) -> Polygon {
+        let scaled = Polygon::new(
+            self.points
+                .iter()
+                .map(|p| (*p - anchor).scale(sx, sy) + anchor)
+                .collect(),
+        );
+
+        if sx * sy < 0.0 {
+            scaled.reversed()
+        } else {
+            scaled
+        }
+    }
+
+    /// Scale a polygon away from its center point, see `scale_around`
+    pub fn scale_around_center(&self, sx: f64, sy: f64) -> Polygon {
+        self.scale_around(self.center(), sx, sy)
+    }
+
+    /// Scale the entire polygon away from the origin, see `scale_around`
+    pub fn scale_around_origin(&self, sx: f64, sy: f64) -> Polygon {
+        self.scale_around(Point::zero(), sx, sy)
+    }
+
+    /// Snap every point of this polygon to a grid with the tolerance's epsilon as the spacing.
+    ///
+    /// Points that end up on top of the one before them are removed. Returns an error if that leaves fewer than
+    /// 3 points, which happens when the polygon is smaller than the tolerance.
+    pub fn snap(&self, tolerance: Tolerance) -> Result<Polygon, Error> {
+        let same = |a: &Point, b: &Point| a.x == b.x && a.y == b.y;
+
+        let mut points: Vec<Point> = Vec::with_capacity(self.len());
+        for p in self.points.iter().map(|p| p.snap(tolerance)) {
+            if points.last().is_none_or(|last| !same(last, &p)) {
+                points.push(p);
+            }
+        }
+        // the ring wraps around so the last point can also be on top of the first.
+        while points.len() > 1 && same(&points[0], &points[points.len() - 1]) {
+            points.pop();
+        }
+
+        Polygon::try_new(points)
+    }
+
+    /// Convert this polygon to i64 coordinates for the exact integer boolean operations, multiplying every coordinate
+    /// by scale and rounding it to the nearest whole number.
+    ///
+    /// Returns an error if any coordinate is not finite or ends up bigger than 2^60.
+    pub fn to_integer(&self, scale: f64) -> Result<Polygon<i64>, Error> {
+        let limit = overlay::MAX_INTEGER_COORDINATE as f64;
+        let mut points = Vec::with_capacity(self.len());
+        for p in self.points.iter() {
+            let scaled = Point::new((p.x * scale).round(), (p.y * scale).round());
+            error::check_finite([&scaled])?;
+            if scaled.x.abs() > limit || scaled.y.abs() > limit {
+                return Err(Error::CoordinateOutOfRange(scaled));
             }
+            points.push(scaled.cast());
         }
 
-        Polygon::new(result_points)
+        Ok(Polygon::new(points))
+    }
+
+    /// Create the union of this polygon and the other polygon provided.
+    ///
+    /// The result can be made of several pieces, if the two polygons don't overlap both of them will be returned,
+    /// and if the union encloses an area that neither polygon covers that will be returned as a hole.
+    pub fn union(&self, other: &Polygon) -> MultiPolygon {
+        self.overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this polygon and the other polygon provided, the area covered by both of them.
+    ///
+    /// This can be several separate pieces if either polygon is concave. If the polygons don't overlap, or only touch
+    /// along an edge or at a point, the result will be empty.
+    pub fn intersection(&self, other: &Polygon) -> MultiPolygon {
+        self.overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other polygon from this one, leaving the parts of this polygon not covered by other.
+    ///
+    /// If other is entirely inside this polygon the result will contain a hole.
+    pub fn difference(&self, other: &Polygon) -> MultiPolygon {
+        self.overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this polygon and the other polygon provided,
+    /// the areas covered by exactly one of them.
+    pub fn symmetric_difference(&self, other: &Polygon) -> MultiPolygon {
+        self.overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this polygon and the other polygon provided.
+    /// Returns an error if either polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_union(&self, other: &Polygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this polygon and the other polygon provided.
+    /// Returns an error if either polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_intersection(&self, other: &Polygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other polygon from this one.
+    /// Returns an error if either polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_difference(&self, other: &Polygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this polygon and the other polygon provided.
+    /// Returns an error if either polygon has a point without finite coordinates,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_symmetric_difference(&self, other: &Polygon) -> Result<MultiPolygon, Error> {
+        self.try_overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this polygon and the other polygon provided.
+    ///
+    /// The points of both polygons are first snapped to a grid with the tolerance's epsilon as the spacing,
+    /// so corners closer together than that are merged rather than leaving tiny slivers in the result.
+    pub fn union_with_tolerance(&self, other: &Polygon, tolerance: Tolerance) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Union, tolerance)
+    }
+
+    /// Create the intersection of this polygon and the other polygon provided,
+    /// snapping the points of both to the tolerance first.
+    pub fn intersection_with_tolerance(
+        &self,
+        other: &Polygon,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Intersection, tolerance)
+    }
+
+    /// Subtract the other polygon from this one, snapping the points of both to the tolerance first.
+    pub fn difference_with_tolerance(&self, other: &Polygon, tolerance: Tolerance) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::Difference, tolerance)
+    }
+
+    /// Create the symmetric difference of this polygon and the other polygon provided,
+    /// snapping the points of both to the tolerance first.
+    pub fn symmetric_difference_with_tolerance(
+        &self,
+        other: &Polygon,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        self.overlay_with_tolerance(other, Operation::SymmetricDifference, tolerance)
+    }
+
+    fn overlay_with_tolerance(
+        &self,
+        other: &Polygon,
+        op: Operation,
+        tolerance: Tolerance,
+    ) -> MultiPolygon {
+        let a: Vec<Point> = self.points.iter().map(|p| p.snap(tolerance)).collect();
+        let b: Vec<Point> = other.points.iter().map(|p| p.snap(tolerance)).collect();
+
+        MultiPolygon::from_rings(overlay::overlay(&[&a], &[&b], op, FillRule::EvenOdd))
+    }
+
+    fn try_overlay(&self, other: &Polygon, op: Operation) -> Result<MultiPolygon, Error> {
+        error::check_finite(self.points.iter().chain(other.points.iter()))?;
+        Ok(MultiPolygon::from_rings(overlay::try_overlay(
+            &[&self.points],
+            &[&other.points],
+            op,
+            FillRule::EvenOdd,
+        )?))
+    }
+
+    fn overlay(&self, other: &Polygon, op: Operation) -> MultiPolygon {
+        MultiPolygon::from_rings(overlay::overlay(
+            &[&self.points],
+            &[&other.points],
+            op,
+            FillRule::EvenOdd,
+        ))
     }
 }
 
-impl PartialEq for Polygon {
-    fn eq(&self, other: &Self) -> bool {
-        // if all the points in both polygons are equal then they are equal
-        if other.len() != self.len() {
-            return false;
+/// Boolean operations on integer coordinates.
+///
+/// Everything is worked out exactly with 128 bit maths, apart from where two sides cross. Crossing points are rounded
+/// to the nearest whole number in the same way on every platform, so the results are always identical.
+/// All coordinates must be within 2^60 of the origin, these will panic otherwise. The `try_` versions return an error
+/// instead.
+impl Polygon<i64> {
+    /// Return twice the signed area of this polygon, worked out exactly.
+    ///
+    /// This is positive if the points go counter clockwise around the polygon and negative if they go clockwise.
+    /// Like the boolean operations this will panic if any coordinate is bigger than `MAX_INTEGER_COORDINATE`, past
+    /// that the 128 bit products could overflow.
+    pub fn doubled_signed_area(&self) -> i128 {
+        let limit = overlay::MAX_INTEGER_COORDINATE as u64;
+        if let Some(p) = self
+            .points
+            .iter()
+            .find(|p| p.x.unsigned_abs() > limit || p.y.unsigned_abs() > limit)
+        {
+            panic!("{}", Error::CoordinateOutOfRange(p.cast()));
         }
 
-        for (a, b) in zip(self.points.iter(), other.points.iter()) {
-            if a != b {
-                return false;
-            }
+        let origin = self.points[0];
+        let mut total: i128 = 0;
+        for pair in self.points[1..].windows(2) {
+            let (ax, ay) = (
+                pair[0].x as i128 - origin.x as i128,
+                pair[0].y as i128 - origin.y as i128,
+            );
+            let (bx, by) = (
+                pair[1].x as i128 - origin.x as i128,
+                pair[1].y as i128 - origin.y as i128,
+            );
+            // the partial sums can overflow even when the total fits, wrapping still gives the right total.
+            total = total.wrapping_add(ax * by - ay * bx);
         }
+        total
+    }
+
+    /// Convert this polygon to f64 coordinates, dividing every coordinate by scale.
+    pub fn to_float(&self, scale: f64) -> Polygon {
+        Polygon::new(
+            self.points
+                .iter()
+                .map(|p| Point::new(p.x as f64 / scale, p.y as f64 / scale))
+                .collect(),
+        )
+    }
+
+    /// Create the union of this polygon and the other polygon provided.
+    pub fn union(&self, other: &Polygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this polygon and the other polygon provided, the area covered by both of them.
+    pub fn intersection(&self, other: &Polygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other polygon from this one, leaving the parts of this polygon not covered by other.
+    pub fn difference(&self, other: &Polygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this polygon and the other polygon provided,
+    /// the areas covered by exactly one of them.
+    pub fn symmetric_difference(&self, other: &Polygon<i64>) -> MultiPolygon<i64> {
+        self.overlay(other, Operation::SymmetricDifference)
+    }
+
+    /// Create the union of this polygon and the other polygon provided.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_union(&self, other: &Polygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Union)
+    }
+
+    /// Create the intersection of this polygon and the other polygon provided.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_intersection(&self, other: &Polygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Intersection)
+    }
+
+    /// Subtract the other polygon from this one.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_difference(&self, other: &Polygon<i64>) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::Difference)
+    }
+
+    /// Create the symmetric difference of this polygon and the other polygon provided.
+    /// Returns an error if a coordinate is more than 2^60 from the origin,
+    /// or if the edges of the result couldn't be joined up into closed rings that don't touch themselves.
+    pub fn try_symmetric_difference(
+        &self,
+        other: &Polygon<i64>,
+    ) -> Result<MultiPolygon<i64>, Error> {
+        self.try_overlay(other, Operation::SymmetricDifference)
+    }
+
+    fn try_overlay(&self, other: &Polygon<i64>, op: Operation) -> Result<MultiPolygon<i64>, Error> {
+        Ok(MultiPolygon::from_integer_rings(
+            overlay::try_integer_overlay(&[&self.points], &[&other.points], op)?,
+        ))
+    }
+
+    fn overlay(&self, other: &Polygon<i64>, op: Operation) -> MultiPolygon<i64> {
+        MultiPolygon::from_integer_rings(overlay::integer_overlay(
+            &[&self.points],
+            &[&other.points],
+            op,
+        ))
+    }
+}
 
-        true
+impl<T: Scalar> PartialEq for Polygon<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.approx_eq(other, Tolerance::default())
     }
 }
 
-impl Display for Polygon {
+impl<T: Scalar> Display for Polygon<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "Poly(")?;
 
@@ -314,9 +801,18 @@ impl Display for Polygon {
 #[cfg(test)]
 mod tests {
 
-    use crate::{point::Point, tests::assert_f64};
+    use crate::{
+        error::Error,
+        multipolygon::MultiPolygon,
+        overlay,
+        point::Point,
+        polygonwithholes::PolygonWithHoles,
+        tests::{assert_f64, square},
+    };
 
-    use super::Polygon;
+    use super::{Boundary, FillRule, Location, Orientation, Polygon, SelfIntersection};
+    use crate::tolerance::Tolerance;
+    use std::f64::consts::TAU;
 
     macro_rules! contains_tests {
         ($($name:ident: $poly_points:expr, $test_point:expr, $expected:expr,)*) => {
@@ -349,93 +845,456 @@ mod tests {
             ],
         Point::new(1.0, 1.0),
         true,
+        on_edge:
+            vec![
+                Point::zero(),
+                Point::new(0.0, 2.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0)
+            ],
+        Point::new(2.0, 1.0),
+        true,
+        on_corner:
+            vec![
+                Point::zero(),
+                Point::new(0.0, 2.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0)
+            ],
+        Point::new(2.0, 2.0),
+        true,
+        in_concave_notch:
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 3.0),
+                Point::new(1.0, 3.0),
+                Point::new(1.0, 1.0),
+                Point::new(2.0, 1.0),
+                Point::new(2.0, 3.0),
+                Point::new(3.0, 3.0),
+                Point::new(3.0, 0.0)
+            ],
+        Point::new(1.5, 2.0),
+        false,
+        level_with_vertex:
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 2.0),
+                Point::new(2.0, 0.0),
+                Point::new(3.0, 2.0),
+                Point::new(4.0, 0.0)
+            ],
+        Point::new(0.5, 0.5),
+        true,
+        inside_counter_clockwise:
+            vec![
+                Point::zero(),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 2.0)
+            ],
+        Point::new(1.0, 1.0),
+        true,
+        just_outside_diagonal:
+            vec![
+                Point::zero(),
+                Point::new(24.0, 24.0),
+                Point::new(24.0, 0.0)
+            ],
+        Point::new(0.5, 0.5 + f64::EPSILON / 2.0),
+        false,
+        just_inside_diagonal:
+            vec![
+                Point::zero(),
+                Point::new(24.0, 24.0),
+                Point::new(24.0, 0.0)
+            ],
+        Point::new(0.5 + f64::EPSILON / 2.0, 0.5),
+        true,
     );
 
-    #[test]
-    fn is_self_intersecting() {
-        let poly = Polygon::new(vec![
-            Point::new(0.0, 0.0),
-            Point::new(0.0, 1.0),
-            Point::new(1.0, 0.0),
-            Point::new(1.0, 1.0),
-        ]);
-
-        assert!(poly.is_self_intersecting())
+    macro_rules! winding_number_tests {
+        ($($name:ident: $poly:expr, $test_point:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($poly.winding_number($test_point), $expected);
+                }
+            )*
+        };
     }
 
-    #[test]
-    fn is_not_self_intersecting() {
-        let poly = Polygon::new(vec![
-            Point::new(0.0, 0.0),
-            Point::new(0.0, 1.0),
-            Point::new(1.0, 1.0),
-            Point::new(1.0, 0.0),
-        ]);
-
-        assert!(!poly.is_self_intersecting())
+    winding_number_tests!(
+        winding_clockwise: square(0.0, 0.0, 2.0), Point::new(1.0, 1.0), Some(-1),
+        winding_counter_clockwise: square(0.0, 0.0, 2.0).reversed(), Point::new(1.0, 1.0), Some(1),
+        winding_outside: square(0.0, 0.0, 2.0), Point::new(3.0, 1.0), Some(0),
+        winding_level_with_top: square(0.0, 0.0, 2.0), Point::new(-1.0, 2.0), Some(0),
+        winding_on_edge: square(0.0, 0.0, 2.0), Point::new(1.0, 0.0), None,
+        winding_on_corner: square(0.0, 0.0, 2.0), Point::new(0.0, 2.0), None,
+        winding_twice:
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 2.0),
+                Point::new(0.0, 0.0),
+                Point::new(3.0, 0.0),
+                Point::new(3.0, 3.0),
+                Point::new(0.0, 3.0),
+            ]),
+        Point::new(1.0, 1.0),
+        Some(2),
+    );
+
+    macro_rules! locate_tests {
+        ($($name:ident: $test_point:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(square(0.0, 0.0, 2.0).locate($test_point), $expected);
+                }
+            )*
+        };
+    }
+
+    locate_tests!(
+        locate_inside: Point::new(1.0, 1.0), Location::Inside,
+        locate_outside: Point::new(3.0, 1.0), Location::Outside,
+        locate_outside_bounds: Point::new(-3.0, 1.0), Location::Outside,
+        locate_first_vertex: Point::new(0.0, 0.0), Location::OnBoundary(Boundary::Vertex(0)),
+        locate_vertex: Point::new(2.0, 2.0), Location::OnBoundary(Boundary::Vertex(2)),
+        locate_last_vertex: Point::new(2.0, 0.0), Location::OnBoundary(Boundary::Vertex(3)),
+        locate_edge: Point::new(0.0, 1.5), Location::OnBoundary(Boundary::Edge(0)),
+        locate_closing_edge: Point::new(0.5, 0.0), Location::OnBoundary(Boundary::Edge(3)),
+        locate_near_edge: Point::new(0.0, 1.5).translate(&Point::new(1e-12, 0.0)), Location::Inside,
+    );
+
+    fn bow_tie() -> Polygon {
+        Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+        ])
+    }
+
+    fn double_loop() -> Polygon {
+        // a 2x2 square and then a 3x3 square around it, both going the same way.
+        Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 3.0),
+            Point::new(0.0, 3.0),
+        ])
+    }
+
+    fn pentagram() -> Polygon {
+        Polygon::new(
+            (0..5)
+                .map(|i| Point::new(0.0, 10.0).rotate((i as f64) * 144.0_f64.to_radians()))
+                .collect(),
+        )
     }
 
+    macro_rules! fill_rule_contains_tests {
+        ($($name:ident: $poly:expr, $test_point:expr, $even_odd:expr, $non_zero:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let poly = $poly;
+                    assert_eq!(poly.contains_with_rule($test_point, FillRule::EvenOdd), $even_odd);
+                    assert_eq!(poly.contains_with_rule($test_point, FillRule::NonZero), $non_zero);
+                }
+            )*
+        };
+    }
+
+    fill_rule_contains_tests!(
+        bow_tie_left: bow_tie(), Point::new(0.5, 1.0), true, true,
+        bow_tie_above: bow_tie(), Point::new(1.0, 1.5), false, false,
+        double_loop_inner: double_loop(), Point::new(1.0, 1.0), false, true,
+        double_loop_outer: double_loop(), Point::new(2.5, 2.5), true, true,
+        pentagram_center: pentagram(), Point::zero(), false, true,
+        pentagram_point: pentagram(), Point::new(0.0, 8.0), true, true,
+    );
+
+    macro_rules! fill_rule_area_tests {
+        ($($name:ident: $poly:expr, $even_odd:expr, $non_zero:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let poly = $poly;
+                    assert_f64!(poly.area_with_rule(FillRule::EvenOdd), $even_odd, epsilon = 1e-9);
+                    assert_f64!(poly.area_with_rule(FillRule::NonZero), $non_zero, epsilon = 1e-9);
+                }
+            )*
+        };
+    }
+
+    fill_rule_area_tests!(
+        area_rule_square: square(0.0, 0.0, 2.0), 4.0, 4.0,
+        area_rule_bow_tie: bow_tie(), 2.0, 2.0,
+        area_rule_double_loop: double_loop(), 5.0, 9.0,
+        area_rule_crossed_twice: Polygon::new(vec![
+            Point::new(4.0, 4.0),
+            Point::new(3.0, 2.0),
+            Point::new(5.0, 4.0),
+            Point::new(3.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(4.0, 3.0),
+            Point::new(1.0, 1.0),
+        ]), 1193.0 / 420.0, 2873.0 / 840.0,
+        area_rule_point_on_edge: Polygon::new(vec![
+            Point::new(3.0, 2.0),
+            Point::new(4.0, 3.0),
+            Point::new(4.0, 5.0),
+            Point::new(5.0, 3.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(4.0, 4.0),
+        ]), 4.5, 4.5,
+    );
+
     #[test]
-    fn sides_square() {
+    fn area_rule_pentagram() {
+        let star = pentagram();
+        let even_odd = star.area_with_rule(FillRule::EvenOdd);
+        let non_zero = star.area_with_rule(FillRule::NonZero);
+
+        // the difference is the pentagon in the middle, whose corners are where the star crosses itself.
+        let (a, b) = star.get_side(0);
+        let (c, d) = star.get_side(2);
+        let corner = crate::geom::crossing_point(a, b, c, d);
+        let radius = (corner.x * corner.x + corner.y * corner.y).sqrt();
+        let pentagon = 2.5 * radius * radius * 72.0_f64.to_radians().sin();
+
+        assert_f64!(non_zero - even_odd, pentagon, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn is_self_intersecting() {
         let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
             Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
+        ]);
+
+        assert!(poly.is_self_intersecting())
+    }
+
+    #[test]
+    fn self_intersections() {
+        let poly = Polygon::new(vec![
             Point::new(0.0, 0.0),
             Point::new(0.0, 1.0),
+            Point::new(1.0, 0.0),
             Point::new(1.0, 1.0),
         ]);
 
-        let result = poly.sides();
+        let expected = vec![SelfIntersection {
+            edge_a: 1,
+            edge_b: 3,
+            point: Point::new(0.5, 0.5),
+        }];
 
-        let expected = vec![
-            (Point::new(1.0, 0.0), Point::new(0.0, 0.0)),
-            (Point::new(0.0, 0.0), Point::new(0.0, 1.0)),
-            (Point::new(0.0, 1.0), Point::new(1.0, 1.0)),
-            (Point::new(1.0, 1.0), Point::new(1.0, 0.0)),
-        ];
+        assert_eq!(poly.self_intersections(), expected);
+    }
 
-        assert_eq!(result, expected);
+    #[test]
+    fn self_intersections_none() {
+        assert_eq!(square(0.0, 0.0, 1.0).self_intersections(), vec![]);
     }
 
     #[test]
-    fn check_area() {
+    fn self_intersections_pentagram() {
+        let result = pentagram().self_intersections();
+
+        assert_eq!(result.len(), 5);
+        for crossing in result {
+            // every crossing is on the small pentagon in the middle.
+            let radius = (crossing.point.x.powi(2) + crossing.point.y.powi(2)).sqrt();
+            assert_f64!(
+                radius,
+                10.0 * 72.0_f64.to_radians().cos() / 36.0_f64.to_radians().cos(),
+                epsilon = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn self_intersections_axis_aligned() {
+        // a shape with a vertical side crossing a horizontal one.
         let poly = Polygon::new(vec![
-            Point::new(1.0, 0.0),
             Point::new(0.0, 0.0),
-            Point::new(0.0, 1.0),
-            Point::new(1.0, 1.0),
+            Point::new(0.0, 2.0),
+            Point::new(3.0, 2.0),
+            Point::new(3.0, 1.0),
+            Point::new(-1.0, 1.0),
+            Point::new(-1.0, 0.0),
         ]);
 
-        let result = poly.area();
+        let expected = vec![SelfIntersection {
+            edge_a: 0,
+            edge_b: 3,
+            point: Point::new(0.0, 1.0),
+        }];
 
-        assert_f64!(result, 1.0);
+        assert_eq!(poly.self_intersections(), expected);
     }
 
     #[test]
-    fn rotate_square() {
+    fn self_intersections_spikes() {
+        // a square with a spike sticking up out of the top, the sides of the spike overlap all the way along.
         let poly = Polygon::new(vec![
             Point::new(0.0, 0.0),
             Point::new(0.0, 1.0),
+            Point::new(0.5, 1.0),
+            Point::new(0.5, 2.0),
+            Point::new(0.5, 1.0),
             Point::new(1.0, 1.0),
             Point::new(1.0, 0.0),
         ]);
 
-        let result = poly.rotate_around_center(90.0_f64.to_radians());
+        let expected = vec![SelfIntersection {
+            edge_a: 2,
+            edge_b: 3,
+            point: Point::new(0.5, 2.0),
+        }];
 
-        // area should be the same after rotating the polygon
-        assert_f64!(result.area(), poly.area());
+        assert!(poly.is_self_intersecting());
+        assert_eq!(poly.self_intersections(), expected);
 
-        let expected = Polygon::new(vec![
+        // the second side folds back half way down the first, ending on it.
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 1.0),
             Point::new(1.0, 0.0),
+        ]);
+
+        let expected = vec![
+            SelfIntersection {
+                edge_a: 0,
+                edge_b: 1,
+                point: Point::new(0.0, 1.0),
+            },
+            SelfIntersection {
+                edge_a: 0,
+                edge_b: 2,
+                point: Point::new(0.0, 1.0),
+            },
+        ];
+
+        assert!(poly.is_self_intersecting());
+        assert_eq!(poly.self_intersections(), expected);
+    }
+
+    #[test]
+    fn make_valid_bow_tie() {
+        let result = bow_tie().make_valid();
+
+        let expected = multi(vec![
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 2.0),
+                Point::new(1.0, 1.0),
+            ]),
+            Polygon::new(vec![
+                Point::new(1.0, 1.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0),
+            ]),
+        ]);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn make_valid_spike_and_duplicates() {
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
             Point::new(0.0, 0.0),
             Point::new(0.0, 1.0),
+            Point::new(0.5, 1.0),
+            Point::new(0.5, 2.0),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 1.0),
             Point::new(1.0, 1.0),
+            Point::new(1.0, 0.0),
         ]);
 
-        assert_eq!(result, expected);
+        assert_eq!(poly.make_valid(), multi(vec![square(0.0, 0.0, 1.0)]));
     }
 
     #[test]
-    fn rotate_square_around_origin() {
+    fn make_valid_already_valid() {
+        let poly = square(0.0, 0.0, 1.0);
+        assert_eq!(poly.make_valid(), multi(vec![poly]));
+    }
+
+    #[test]
+    fn make_valid_pentagram() {
+        let star = pentagram();
+
+        let non_zero = star.make_valid();
+        assert_eq!(non_zero.len(), 1);
+        assert!(non_zero.contains(Point::zero()));
+
+        // with even odd the middle is cut out leaving five separate points.
+        let even_odd = star.make_valid_with_rule(FillRule::EvenOdd);
+        assert_eq!(even_odd.len(), 5);
+        assert!(!even_odd.contains(Point::new(0.0, 1.0)));
+
+        for poly in non_zero.iter().chain(even_odd.iter()) {
+            assert!(!poly.exterior.is_self_intersecting());
+        }
+    }
+
+    #[test]
+    fn make_valid_touching_rings() {
+        // a square with a diamond going the other way round inside it, touching its bottom edge at (2, 0)
+        let diamond = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(3.0, 1.0),
+            Point::new(2.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(0.0, 4.0),
+        ]);
+
+        let result = diamond.make_valid();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.polygons[0].interiors.len(), 1);
+        assert_f64!(result.area(), 14.0);
+
+        // (4, 4) is on the edge from (4, 3) to (4, 5) and has to stay in the ring running along it
+        let folded = Polygon::new(vec![
+            Point::new(3.0, 2.0),
+            Point::new(4.0, 3.0),
+            Point::new(4.0, 5.0),
+            Point::new(5.0, 3.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(4.0, 4.0),
+        ]);
+
+        for poly in [diamond, folded] {
+            for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+                assert_valid_rings(&poly.make_valid_with_rule(rule));
+            }
+        }
+    }
+
+    #[test]
+    fn is_not_self_intersecting() {
         let poly = Polygon::new(vec![
             Point::new(0.0, 0.0),
             Point::new(0.0, 1.0),
@@ -443,96 +1302,400 @@ mod tests {
             Point::new(1.0, 0.0),
         ]);
 
-        let result = poly.rotate_around_origin(90.0_f64.to_radians());
-        let expected = Polygon::new(vec![
+        assert!(!poly.is_self_intersecting())
+    }
+
+    #[test]
+    fn try_new_too_few_points() {
+        let result = Polygon::try_new(vec![Point::new(0.0, 0.0), Point::new(0.0, 1.0)]);
+
+        assert_eq!(result.unwrap_err(), Error::TooFewPoints(2));
+    }
+
+    #[test]
+    fn try_new_not_finite() {
+        let result = Polygon::try_new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, f64::NAN),
+            Point::new(1.0, 1.0),
+        ]);
+
+        assert!(matches!(result, Err(Error::NonFiniteCoordinate(_))));
+    }
+
+    #[test]
+    fn try_area_self_intersecting() {
+        let poly = Polygon::new(vec![
             Point::new(0.0, 0.0),
-            Point::new(-1.0, 0.0),
-            Point::new(-1.0, 1.0),
             Point::new(0.0, 1.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
         ]);
 
-        assert_eq!(result, expected);
+        assert_eq!(poly.try_area(), Err(Error::SelfIntersecting));
+    }
 
-        assert_f64!(result.area(), poly.area());
+    #[test]
+    fn try_union_not_finite() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = Polygon::new(vec![
+            Point::new(0.5, 0.5),
+            Point::new(0.5, f64::INFINITY),
+            Point::new(1.5, 0.5),
+        ]);
+
+        assert!(matches!(
+            a.try_union(&b),
+            Err(Error::NonFiniteCoordinate(_))
+        ));
+        assert_eq!(a.try_union(&a).unwrap(), a.union(&a));
     }
 
-    macro_rules! intersection_tests {
-        ($($name:ident: $points_a:expr, $points_b:expr, $expected:expr,)*) => {
+    macro_rules! orientation_tests {
+        ($($name:ident: $poly_points:expr, $expected:expr,)*) => {
             $(
                 #[test]
                 fn $name() {
-                    let a = Polygon::new($points_a);
-                    let b = Polygon::new($points_b);
-
-                    assert_eq!(a.intersects(&b), $expected);
+                    let poly = Polygon::new($poly_points);
+                    assert_eq!(poly.orientation(), $expected);
                 }
             )*
         };
     }
 
-    intersection_tests!(
-        non_intersecting:
+    orientation_tests!(
+        orientation_clockwise:
             vec![
                 Point::new(0.0, 0.0),
-                Point::new(1.0, 1.0),
-                Point::new(1.0, 0.0)
-            ],
-        vec![
-            Point::new(2.0, 2.0),
-            Point::new(3.0, 3.0),
-            Point::new(3.0, 2.0)
-        ],
-        false,
-        corner_intersecting:
-            vec![
                 Point::new(0.0, 1.0),
                 Point::new(1.0, 1.0),
                 Point::new(1.0, 0.0)
             ],
-        vec![
-            Point::new(1.0, 1.0),
-            Point::new(1.0, 2.0),
-            Point::new(2.0, 1.0)
-        ],
-        true,
-        overlapping:
+        Orientation::Clockwise,
+        orientation_counter_clockwise:
             vec![
-                Point::new(0.0, 1.0),
-                Point::new(1.0, 1.0),
+                Point::new(0.0, 0.0),
                 Point::new(1.0, 0.0),
-                Point::new(0.0, 0.0)
-            ],
-        vec![
-            Point::new(0.5, 1.5),
-            Point::new(1.5, 1.5),
-            Point::new(1.5, 0.5),
-            Point::new(0.5, 0.5)
-        ],
-        true,
-        containing:
-            vec![
-                Point::new(0.0, 2.0),
-                Point::new(2.0, 2.0),
-                Point::new(2.0, 0.0),
-                Point::new(0.0, 0.0)
+                Point::new(1.0, 1.0),
+                Point::new(0.0, 1.0)
             ],
-        vec![
-            Point::new(0.5, 1.5),
-            Point::new(1.5, 1.5),
-            Point::new(1.5, 0.5),
-            Point::new(0.5, 0.5)
-        ],
-        true,
-        contained:
+        Orientation::CounterClockwise,
+        orientation_degenerate:
             vec![
-                Point::new(0.5, 1.5),
-                Point::new(1.5, 1.5),
-                Point::new(1.5, 0.5),
-                Point::new(0.5, 0.5)
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 1.0),
+                Point::new(2.0, 2.0)
             ],
-        vec![
-            Point::new(0.0, 2.0),
-            Point::new(2.0, 2.0),
+        Orientation::Degenerate,
+    );
+
+    #[test]
+    fn reversed() {
+        let poly = square(0.0, 0.0, 1.0);
+
+        let expected = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.0, 1.0),
+        ]);
+
+        assert_eq!(poly.reversed(), expected);
+        assert_eq!(poly.reversed().reversed(), poly);
+    }
+
+    #[test]
+    fn normalise_orientation() {
+        let clockwise = square(0.0, 0.0, 1.0);
+        let counter_clockwise = clockwise.reversed();
+
+        assert_eq!(clockwise.to_clockwise(), clockwise);
+        assert_eq!(counter_clockwise.to_clockwise(), clockwise);
+        assert_eq!(clockwise.to_counter_clockwise(), counter_clockwise);
+        assert_eq!(counter_clockwise.to_counter_clockwise(), counter_clockwise);
+    }
+
+    #[test]
+    fn sides_square() {
+        let poly = Polygon::new(vec![
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+        ]);
+
+        let result = poly.sides();
+
+        let expected = vec![
+            (Point::new(1.0, 0.0), Point::new(0.0, 0.0)),
+            (Point::new(0.0, 0.0), Point::new(0.0, 1.0)),
+            (Point::new(0.0, 1.0), Point::new(1.0, 1.0)),
+            (Point::new(1.0, 1.0), Point::new(1.0, 0.0)),
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn check_area() {
+        let poly = Polygon::new(vec![
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+        ]);
+
+        let result = poly.area();
+
+        assert_f64!(result, 1.0);
+    }
+
+    #[test]
+    fn check_signed_area() {
+        let poly = square(0.0, 0.0, 2.0);
+
+        assert_f64!(poly.signed_area(), -4.0);
+        assert_f64!(poly.reversed().signed_area(), 4.0);
+        assert_f64!(poly.reversed().area(), 4.0);
+    }
+
+    fn area_test_shapes() -> Vec<Polygon> {
+        let circle = (0..360)
+            .step_by(10)
+            .map(|a| Point::new(3.0, 0.0).rotate(-(a as f64).to_radians()))
+            .collect();
+
+        vec![
+            square(0.0, 0.0, 1.0),
+            Polygon::new(vec![
+                Point::new(0.0, 1.0),
+                Point::new(3.0, 6.0),
+                Point::new(6.0, 2.0),
+            ]),
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 3.0),
+                Point::new(1.0, 3.0),
+                Point::new(1.0, 1.0),
+                Point::new(4.0, 1.0),
+                Point::new(4.0, 0.0),
+            ]),
+            Polygon::new(circle),
+        ]
+    }
+
+    #[test]
+    fn area_unchanged_by_rotation_and_translation() {
+        let angles = [0.0, 0.3, 1.0, 2.5, std::f64::consts::PI];
+        let offsets = [
+            Point::zero(),
+            Point::new(10.0, -5.0),
+            Point::new(-250.0, 1000.0),
+            Point::new(1e6, 1e6),
+        ];
+
+        for poly in area_test_shapes() {
+            let area = poly.area();
+            let signed_area = poly.signed_area();
+            assert_f64!(area, signed_area.abs());
+            assert_f64!(poly.reversed().signed_area(), -signed_area, epsilon = 1e-9);
+
+            for angle in angles {
+                for offset in offsets {
+                    let moved = poly.rotate_around_center(angle).translate(offset);
+                    assert_f64!(moved.area(), area, epsilon = 1e-6);
+                    assert_f64!(moved.signed_area(), signed_area, epsilon = 1e-6);
+
+                    let moved = poly.rotate_around_origin(angle).translate(offset);
+                    assert_f64!(moved.area(), area, epsilon = 1e-6);
+                    assert_f64!(moved.signed_area(), signed_area, epsilon = 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_square() {
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 0.0),
+        ]);
+
+        let result = poly.rotate_around_center(90.0_f64.to_radians());
+
+        // area should be the same after rotating the polygon
+        assert_f64!(result.area(), poly.area());
+
+        let expected = Polygon::new(vec![
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+        ]);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn rotate_square_around_origin() {
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 0.0),
+        ]);
+
+        let result = poly.rotate_around_origin(90.0_f64.to_radians());
+        let expected = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(-1.0, 0.0),
+            Point::new(-1.0, 1.0),
+            Point::new(0.0, 1.0),
+        ]);
+
+        assert_eq!(result, expected);
+
+        assert_f64!(result.area(), poly.area());
+    }
+
+    macro_rules! scale_tests {
+        ($($name:ident: $poly:expr, $anchor:expr, $sx:expr, $sy:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let poly = $poly;
+                    let (sx, sy): (f64, f64) = ($sx, $sy);
+                    let result = poly.scale_around($anchor, sx, sy);
+
+                    assert_eq!(result, $expected);
+                    assert_f64!(result.area(), poly.area() * (sx * sy).abs());
+                    assert_eq!(result.orientation(), poly.orientation());
+                }
+            )*
+        };
+    }
+
+    scale_tests!(
+        scale_up: square(1.0, 1.0, 2.0), Point::zero(), 2.0, 2.0, square(2.0, 2.0, 4.0),
+        scale_down_around_corner: square(1.0, 1.0, 2.0), Point::new(1.0, 1.0), 0.5, 0.5, square(1.0, 1.0, 1.0),
+        scale_stretch: square(0.0, 0.0, 1.0), Point::zero(), 3.0, 1.0,
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 1.0),
+                Point::new(3.0, 1.0),
+                Point::new(3.0, 0.0),
+            ]),
+        scale_mirror_x: square(1.0, 0.0, 1.0), Point::zero(), -1.0, 1.0,
+            Polygon::new(vec![
+                Point::new(-1.0, 0.0),
+                Point::new(-2.0, 0.0),
+                Point::new(-2.0, 1.0),
+                Point::new(-1.0, 1.0),
+            ]),
+        scale_mirror_y: square(0.0, 1.0, 1.0), Point::zero(), 1.0, -2.0,
+            Polygon::new(vec![
+                Point::new(0.0, -2.0),
+                Point::new(1.0, -2.0),
+                Point::new(1.0, -4.0),
+                Point::new(0.0, -4.0),
+            ]),
+        scale_flip_both: square(0.0, 0.0, 2.0), Point::new(1.0, 1.0), -1.0, -1.0,
+            Polygon::new(vec![
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 2.0),
+            ]),
+    );
+
+    #[test]
+    fn scale_around_center_and_origin() {
+        let poly = square(1.0, 1.0, 2.0);
+
+        assert_eq!(poly.scale_around_center(0.5, 0.5), square(1.5, 1.5, 1.0));
+        assert_eq!(poly.scale_around_origin(0.5, 0.5), square(0.5, 0.5, 1.0));
+        assert_eq!(poly.scale_around_center(-1.0, 1.0).center(), poly.center());
+    }
+
+    macro_rules! intersection_tests {
+        ($($name:ident: $points_a:expr, $points_b:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let a = Polygon::new($points_a);
+                    let b = Polygon::new($points_b);
+
+                    assert_eq!(a.intersects(&b), $expected);
+                }
+            )*
+        };
+    }
+
+    intersection_tests!(
+        non_intersecting:
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 1.0),
+                Point::new(1.0, 0.0)
+            ],
+        vec![
+            Point::new(2.0, 2.0),
+            Point::new(3.0, 3.0),
+            Point::new(3.0, 2.0)
+        ],
+        false,
+        corner_intersecting:
+            vec![
+                Point::new(0.0, 1.0),
+                Point::new(1.0, 1.0),
+                Point::new(1.0, 0.0)
+            ],
+        vec![
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 1.0)
+        ],
+        true,
+        overlapping:
+            vec![
+                Point::new(0.0, 1.0),
+                Point::new(1.0, 1.0),
+                Point::new(1.0, 0.0),
+                Point::new(0.0, 0.0)
+            ],
+        vec![
+            Point::new(0.5, 1.5),
+            Point::new(1.5, 1.5),
+            Point::new(1.5, 0.5),
+            Point::new(0.5, 0.5)
+        ],
+        true,
+        containing:
+            vec![
+                Point::new(0.0, 2.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 0.0)
+            ],
+        vec![
+            Point::new(0.5, 1.5),
+            Point::new(1.5, 1.5),
+            Point::new(1.5, 0.5),
+            Point::new(0.5, 0.5)
+        ],
+        true,
+        contained:
+            vec![
+                Point::new(0.5, 1.5),
+                Point::new(1.5, 1.5),
+                Point::new(1.5, 0.5),
+                Point::new(0.5, 0.5)
+            ],
+        vec![
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
             Point::new(2.0, 0.0),
             Point::new(0.0, 0.0)
         ],
@@ -568,6 +1731,834 @@ mod tests {
 
         let result = a.union(&b);
 
-        assert_eq!(result, expected);
+        assert_eq!(result, multi(vec![expected]));
+    }
+
+    fn multi(polygons: Vec<Polygon>) -> MultiPolygon {
+        polygons.into_iter().map(PolygonWithHoles::from).collect()
+    }
+
+    fn assert_valid_rings(result: &MultiPolygon) {
+        for poly in &result.polygons {
+            for ring in std::iter::once(&poly.exterior).chain(&poly.interiors) {
+                assert!(!ring.is_self_intersecting(), "{:?}", ring);
+                assert!(ring.try_area().is_ok(), "{:?}", ring);
+            }
+        }
+    }
+
+    #[test]
+    fn union_start_inside_other() {
+        let a = square(0.5, 0.5, 1.0);
+        let b = square(0.0, 0.0, 1.0);
+
+        let result = a.union(&b);
+
+        assert_eq!(result.len(), 1);
+        assert_f64!(result.area(), 1.75);
+    }
+
+    #[test]
+    fn union_disjoint() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(2.0, 2.0, 1.0);
+
+        let result = a.union(&b);
+
+        assert_eq!(result, multi(vec![a, b]));
+    }
+
+    #[test]
+    fn union_contained() {
+        let a = square(0.0, 0.0, 3.0);
+        let b = square(1.0, 1.0, 1.0);
+
+        assert_eq!(a.union(&b), multi(vec![a.clone()]));
+        assert_eq!(b.union(&a), multi(vec![a]));
+    }
+
+    #[test]
+    fn union_shared_edge() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(1.0, 0.0, 1.0);
+
+        let expected = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 0.0),
+        ]);
+
+        assert_eq!(a.union(&b), multi(vec![expected]));
+    }
+
+    #[test]
+    fn union_partially_shared_edge() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = square(2.0, 1.0, 2.0);
+
+        let expected = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(4.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 0.0),
+        ]);
+
+        assert_eq!(a.union(&b), multi(vec![expected]));
+    }
+
+    #[test]
+    fn union_touching_corner() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(1.0, 1.0, 1.0);
+
+        assert_eq!(a.union(&b), multi(vec![a, b]));
+    }
+
+    #[test]
+    fn union_with_hole() {
+        // a U shape with a bar across the top leaves a hole in the middle.
+        let a = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 3.0),
+            Point::new(1.0, 3.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 3.0),
+            Point::new(3.0, 3.0),
+            Point::new(3.0, 0.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 4.0),
+            Point::new(3.0, 4.0),
+            Point::new(3.0, 2.0),
+        ]);
+
+        let expected_outer = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 4.0),
+            Point::new(3.0, 4.0),
+            Point::new(3.0, 0.0),
+        ]);
+        let expected_hole = Polygon::new(vec![
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(1.0, 2.0),
+        ]);
+
+        let expected = PolygonWithHoles::new(expected_outer, vec![expected_hole]);
+
+        assert_eq!(a.union(&b), MultiPolygon::from(expected));
+    }
+
+    #[test]
+    fn basic_intersection() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(0.5, 0.5, 1.0);
+
+        assert_eq!(a.intersection(&b), multi(vec![square(0.5, 0.5, 0.5)]));
+    }
+
+    #[test]
+    fn intersection_contained() {
+        let a = square(0.0, 0.0, 3.0);
+        let b = square(1.0, 1.0, 1.0);
+
+        assert_eq!(a.intersection(&b), multi(vec![b.clone()]));
+        assert_eq!(b.intersection(&a), multi(vec![b]));
+    }
+
+    #[test]
+    fn intersection_concave() {
+        // a bar across the arms of a U shape gives two separate pieces.
+        let a = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 3.0),
+            Point::new(1.0, 3.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 3.0),
+            Point::new(3.0, 3.0),
+            Point::new(3.0, 0.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(-1.0, 2.0),
+            Point::new(-1.0, 4.0),
+            Point::new(4.0, 4.0),
+            Point::new(4.0, 2.0),
+        ]);
+
+        let result = a.intersection(&b);
+
+        assert_eq!(
+            result,
+            multi(vec![square(0.0, 2.0, 1.0), square(2.0, 2.0, 1.0)])
+        );
+    }
+
+    macro_rules! empty_intersection_tests {
+        ($($name:ident: $a:expr, $b:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert!($a.intersection(&$b).is_empty());
+                }
+            )*
+        };
+    }
+
+    empty_intersection_tests!(
+        intersection_disjoint: square(0.0, 0.0, 1.0), square(2.0, 2.0, 1.0),
+        intersection_shared_edge: square(0.0, 0.0, 1.0), square(1.0, 0.0, 1.0),
+        intersection_touching_corner: square(0.0, 0.0, 1.0), square(1.0, 1.0, 1.0),
+    );
+
+    #[test]
+    fn intersection_self_intersecting() {
+        let result = bow_tie().intersection(&square(0.0, 0.0, 2.0));
+        assert_eq!(result.len(), 2);
+        assert_f64!(result.area(), 2.0);
+
+        // the pentagon in the middle of the star isn't filled with the even odd rule
+        let star = pentagram();
+        let result = star.intersection(&square(-20.0, -20.0, 40.0));
+        assert_eq!(result.len(), 5);
+        assert_f64!(
+            result.area(),
+            star.area_with_rule(FillRule::EvenOdd),
+            epsilon = 1e-9
+        );
+        assert!(!result.contains(Point::zero()));
+    }
+
+    #[test]
+    fn intersection_crossings_at_one_point() {
+        // two bow ties crossing at (1, 1), the four triangles only share edges
+        let across = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
+        ]);
+        assert!(bow_tie().intersection(&across).is_empty());
+        assert_f64!(bow_tie().union(&across).area(), 4.0);
+
+        // the same again turned so the crossing points have to be rounded
+        let turn = |poly: &Polygon| {
+            poly.translate(Point::new(-1.0, -1.0))
+                .rotate_around_origin(0.3)
+        };
+        assert_f64!(
+            turn(&bow_tie()).intersection(&turn(&across)).area(),
+            0.0,
+            epsilon = 1e-9
+        );
+        assert_f64!(
+            turn(&bow_tie()).union(&turn(&across)).area(),
+            4.0,
+            epsilon = 1e-9
+        );
+
+        // a star crossed by lines through its middle
+        let star = pentagram();
+        let spokes = Polygon::new(vec![
+            Point::new(-12.0, -1.0),
+            Point::new(12.0, 1.0),
+            Point::new(12.0, -1.0),
+            Point::new(-12.0, 1.0),
+        ]);
+        let intersection = star.intersection(&spokes).area();
+        let a_minus_b = star.difference(&spokes).area();
+        assert_f64!(
+            a_minus_b + intersection,
+            star.area_with_rule(FillRule::EvenOdd),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn intersection_touching_at_a_point() {
+        // two squares notched from opposite sides, leaving triangles that only touch at (2, 2)
+        let a = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 4.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(0.0, 4.0),
+        ]);
+
+        let intersection = a.intersection(&b);
+
+        assert_eq!(intersection.len(), 2);
+        assert_valid_rings(&intersection);
+        assert_f64!(intersection.area(), 8.0);
+    }
+
+    #[test]
+    fn basic_difference() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(0.5, 0.5, 1.0);
+
+        let expected = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(0.5, 1.0),
+            Point::new(0.5, 0.5),
+            Point::new(1.0, 0.5),
+            Point::new(1.0, 0.0),
+        ]);
+
+        assert_eq!(a.difference(&b), multi(vec![expected]));
+    }
+
+    #[test]
+    fn difference_makes_hole() {
+        let a = square(0.0, 0.0, 3.0);
+        let b = square(1.0, 1.0, 1.0);
+
+        let hole = Polygon::new(vec![
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(1.0, 2.0),
+        ]);
+
+        let expected = PolygonWithHoles::new(a.clone(), vec![hole]);
+
+        assert_eq!(a.difference(&b), MultiPolygon::from(expected));
+        assert!(b.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn difference_disjoint() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(1.0, 0.0, 1.0);
+
+        assert_eq!(a.difference(&b), multi(vec![a]));
+    }
+
+    #[test]
+    fn difference_leaves_valid_rings() {
+        // b is inside a apart from touching the corner at (2, 3), so the hole it leaves touches the outside there
+        let a = Polygon::new(vec![
+            Point::new(3.0, 4.0),
+            Point::new(1.0, 3.0),
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(2.0, 3.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(1.0, 2.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 3.0),
+        ]);
+
+        let difference = a.difference(&b);
+
+        assert_eq!(difference.len(), 1);
+        assert_eq!(difference.polygons[0].interiors.len(), 1);
+        assert_valid_rings(&difference);
+        assert_f64!(
+            difference.area(),
+            a.area() - a.intersection(&b).area(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn basic_symmetric_difference() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(0.5, 0.5, 1.0);
+
+        let expected = vec![
+            Polygon::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 1.0),
+                Point::new(0.5, 1.0),
+                Point::new(0.5, 0.5),
+                Point::new(1.0, 0.5),
+                Point::new(1.0, 0.0),
+            ]),
+            Polygon::new(vec![
+                Point::new(0.5, 1.0),
+                Point::new(0.5, 1.5),
+                Point::new(1.5, 1.5),
+                Point::new(1.5, 0.5),
+                Point::new(1.0, 0.5),
+                Point::new(1.0, 1.0),
+            ]),
+        ];
+
+        let result = a.symmetric_difference(&b);
+
+        assert_eq!(result.len(), 2);
+        for ring in expected {
+            assert!(
+                result.iter().any(|p| p.exterior == ring),
+                "missing {}",
+                ring
+            );
+        }
+    }
+
+    #[test]
+    fn symmetric_difference_leaves_valid_rings() {
+        // b is inside a apart from touching the corner at (2, 3), so the hole it leaves touches the outside there
+        let a = Polygon::new(vec![
+            Point::new(3.0, 4.0),
+            Point::new(1.0, 3.0),
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(2.0, 3.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(1.0, 2.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 3.0),
+        ]);
+
+        let xor = a.symmetric_difference(&b);
+
+        assert_eq!(xor.len(), 1);
+        assert_eq!(xor.polygons[0].interiors.len(), 1);
+        assert_valid_rings(&xor);
+        assert_f64!(
+            xor.area(),
+            a.area() + b.area() - 2.0 * a.intersection(&b).area(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn symmetric_difference_same() {
+        let a = square(0.0, 0.0, 1.0);
+
+        assert!(a.symmetric_difference(&a).is_empty());
+    }
+
+    macro_rules! boolean_consistency_tests {
+        ($($name:ident: $a:expr, $b:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let a = $a;
+                    let b = $b;
+
+                    let union = a.union(&b).area();
+                    let intersection = a.intersection(&b).area();
+                    let a_minus_b = a.difference(&b).area();
+                    let b_minus_a = b.difference(&a).area();
+
+                    assert_f64!(a_minus_b + intersection, a.area());
+                    assert_f64!(b_minus_a + intersection, b.area());
+                    assert_f64!(a_minus_b + b_minus_a + intersection, union);
+
+                    let xor = a.symmetric_difference(&b).area();
+                    assert_f64!(xor, a.area() + b.area() - 2.0 * intersection, epsilon = 1e-12);
+                    assert_f64!(xor, a_minus_b + b_minus_a, epsilon = 1e-12);
+                }
+            )*
+        };
+    }
+
+    boolean_consistency_tests!(
+        consistency_overlapping: square(0.0, 0.0, 1.0), square(0.5, 0.5, 1.0),
+        consistency_contained: square(0.0, 0.0, 3.0), square(1.0, 1.0, 1.0),
+        consistency_disjoint: square(0.0, 0.0, 1.0), square(2.0, 0.0, 1.0),
+        consistency_rotated: square(0.0, 0.0, 2.0), square(0.0, 0.0, 2.0).rotate_around_center(0.3),
+    );
+
+    #[test]
+    fn overlay_large_polygons() {
+        // comparing every pair of sides of these would take far too long
+        let circle = |center: Point| {
+            let n = 20_000;
+            Polygon::new(
+                (0..n)
+                    .map(|i| Point::new(10.0, 0.0).rotate(-(i as f64) * TAU / n as f64) + center)
+                    .collect(),
+            )
+        };
+        let a = circle(Point::zero());
+        let b = circle(Point::new(5.0, 0.5));
+
+        let union = a.union(&b);
+        let intersection = a.intersection(&b);
+
+        assert_eq!(union.len(), 1);
+        assert_eq!(intersection.len(), 1);
+        assert_f64!(
+            union.area() + intersection.area(),
+            a.area() + b.area(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn crossings_rounded_apart() {
+        // several of the crossing points here come out a rounding error away from each other
+        let a = Polygon::new(
+            [
+                (0.0, 2.0),
+                (1.0, 1.0),
+                (4.0, 1.0),
+                (3.0, 3.0),
+                (2.0, 1.0),
+                (4.0, 0.0),
+            ]
+            .map(Point::from)
+            .to_vec(),
+        );
+        let b = Polygon::new(
+            [(2.0, 3.0), (2.0, 0.0), (3.0, 3.0), (4.0, 4.0), (4.0, 0.0)]
+                .map(Point::from)
+                .to_vec(),
+        );
+
+        let union = a.union(&b);
+        let intersection = a.intersection(&b).area();
+        let a_minus_b = a.difference(&b).area();
+        let b_minus_a = b.difference(&a).area();
+
+        assert_f64!(
+            a_minus_b + intersection,
+            a.area_with_rule(FillRule::EvenOdd)
+        );
+        assert_f64!(
+            b_minus_a + intersection,
+            b.area_with_rule(FillRule::EvenOdd)
+        );
+        assert_f64!(a_minus_b + b_minus_a + intersection, union.area());
+
+        for polygon in union.iter() {
+            let points = &polygon.exterior.points;
+            for (i, p) in points.iter().enumerate() {
+                for q in &points[i + 1..] {
+                    assert!(p.distance_to(q) > 1e-9, "{p} and {q} should be one point");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn union_pinches_off_hole() {
+        // the union closes off a hole that touches the outside of it at (2, 1)
+        let a = Polygon::new(vec![
+            Point::new(3.0, 3.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+        ]);
+        let b = Polygon::new(vec![
+            Point::new(0.0, 2.0),
+            Point::new(1.0, 3.0),
+            Point::new(2.0, 1.0),
+        ]);
+
+        let union = a.union(&b);
+
+        assert_eq!(union.len(), 1);
+        assert_eq!(union.polygons[0].interiors.len(), 1);
+        assert!(union.polygons[0].interiors[0]
+            .points
+            .contains(&Point::new(2.0, 1.0)));
+        assert!(union.polygons[0]
+            .exterior
+            .points
+            .contains(&Point::new(2.0, 1.0)));
+        assert_f64!(
+            union.area(),
+            a.area() + b.area() - a.intersection(&b).area(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn union_counter_clockwise_input() {
+        let a = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.0, 1.0),
+        ]);
+        let b = square(0.5, 0.5, 1.0);
+
+        let result = a.union(&b);
+
+        assert_eq!(result, square(0.0, 0.0, 1.0).union(&b));
+    }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let a = square(0.0, 0.0, 1000.0);
+        let b = square(0.0, 0.0, 1000.01);
+
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, Tolerance::new(0.1, 2)));
+        assert!(!a.approx_eq(&square(0.0, 0.0, 999.0), Tolerance::new(0.1, 2)));
+    }
+
+    #[test]
+    fn snap() {
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.01, 0.01),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.02, -0.01),
+        ]);
+
+        let result = poly.snap(Tolerance::new(0.5, 0)).unwrap();
+
+        assert_eq!(result, square(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn snap_too_small() {
+        let tiny = square(0.0, 0.0, 0.1);
+
+        assert_eq!(
+            tiny.snap(Tolerance::new(1.0, 0)),
+            Err(Error::TooFewPoints(1))
+        );
+    }
+
+    #[test]
+    fn union_with_tolerance_closes_gap() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(1.0 + 1e-9, 0.0, 1.0);
+
+        assert_eq!(a.union(&b).len(), 2);
+
+        let result = a.union_with_tolerance(&b, Tolerance::new(1e-6, 2));
+        assert_eq!(result.len(), 1);
+        assert_f64!(result.area(), 2.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn difference_with_tolerance_straightens_edge() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = Polygon::new(vec![
+            Point::new(-1.0, -1.0),
+            Point::new(-1.0, 3.0),
+            Point::new(1.0 - 1e-9, 3.0),
+            Point::new(1.0 + 1e-9, -1.0),
+        ]);
+        let expected = Polygon::new(vec![
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+        ]);
+        // a power of two so the grid lines up exactly with whole numbers
+        let tolerance = Tolerance::new(2.0_f64.powi(-20), 2);
+
+        let result = a.difference(&b);
+        assert_eq!(result.len(), 1);
+        assert!(!result.polygons[0]
+            .exterior
+            .approx_eq(&expected, Tolerance::exact()));
+
+        let result = a.difference_with_tolerance(&b, tolerance);
+        assert_eq!(result.len(), 1);
+        assert!(result.polygons[0]
+            .exterior
+            .approx_eq(&expected, Tolerance::exact()));
+    }
+
+    #[test]
+    fn integer_coordinates() {
+        let poly: Polygon<i64> = Polygon::new(vec![
+            Point::from_xy(0, 0),
+            Point::from_xy(0, 1 << 40),
+            Point::from_xy(1 << 40, 1 << 40),
+            Point::from_xy(1 << 40, 0),
+        ]);
+
+        assert!(poly.contains(Point::from_xy(1, 1)));
+        assert!(poly.contains(Point::from_xy(0, 5)));
+        assert!(!poly.contains(Point::from_xy(-1, 5)));
+        assert_eq!(
+            poly.locate(Point::from_xy(1 << 40, 7)),
+            Location::OnBoundary(Boundary::Edge(2))
+        );
+        assert!(!poly.is_self_intersecting());
+
+        let other = poly.translate(Point::from_xy(1 << 40, 1 << 40));
+        assert!(poly.intersects(&other));
+        assert!(!poly.intersects(&other.translate(Point::from_xy(1, 0))));
+    }
+
+    #[test]
+    fn integer_bow_tie() {
+        let poly: Polygon<i32> = Polygon::new(vec![
+            Point::from_xy(0, 0),
+            Point::from_xy(2, 2),
+            Point::from_xy(2, 0),
+            Point::from_xy(0, 2),
+        ]);
+
+        assert!(poly.is_self_intersecting());
+        assert_eq!(poly.winding_number(Point::from_xy(1, 0)), Some(0));
+        assert_eq!(poly.winding_number(Point::from_xy(1, 1)), None);
+    }
+
+    #[test]
+    fn f32_coordinates() {
+        let poly = square(0.0, 0.0, 2.0).cast::<f32>();
+
+        assert!(poly.contains(Point::from_xy(1.0_f32, 1.0)));
+        assert!(!poly.contains(Point::from_xy(3.0_f32, 1.0)));
+        assert_eq!(poly.cast::<f64>(), square(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn cast_rounds_to_integers() {
+        let poly = square(0.4, 0.6, 1.0).cast::<i32>();
+
+        assert_eq!(
+            poly.points,
+            vec![
+                Point::from_xy(0, 1),
+                Point::from_xy(0, 2),
+                Point::from_xy(1, 2),
+                Point::from_xy(1, 1),
+            ]
+        );
+    }
+
+    fn integer_square(x: i64, y: i64, size: i64) -> Polygon<i64> {
+        Polygon::new(vec![
+            Point::from_xy(x, y),
+            Point::from_xy(x, y + size),
+            Point::from_xy(x + size, y + size),
+            Point::from_xy(x + size, y),
+        ])
+    }
+
+    #[test]
+    fn doubled_signed_area_at_the_limit() {
+        let limit = overlay::MAX_INTEGER_COORDINATE;
+        let poly = integer_square(-limit, -limit, 2 * limit);
+        assert_eq!(poly.doubled_signed_area(), -(1 << 123));
+    }
+
+    #[test]
+    #[should_panic(expected = "too big for integer coordinates")]
+    fn doubled_signed_area_out_of_range() {
+        let poly = integer_square(0, 0, i64::MAX);
+        poly.doubled_signed_area();
+    }
+
+    macro_rules! integer_overlay_tests {
+        ($($name:ident: $op:ident, $a:expr, $b:expr, $polygons:expr, $doubled_area:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = $a.$op(&$b);
+                    assert_eq!(result.len(), $polygons);
+                    assert_eq!(result.doubled_area(), $doubled_area);
+                    for poly in result.iter() {
+                        assert!(poly.exterior.doubled_signed_area() < 0);
+                    }
+                }
+            )*
+        };
+    }
+
+    integer_overlay_tests!(
+        integer_union: union, integer_square(0, 0, 10), integer_square(5, 5, 10), 1, 350,
+        integer_intersection: intersection, integer_square(0, 0, 10), integer_square(5, 5, 10), 1, 50,
+        integer_difference: difference, integer_square(0, 0, 10), integer_square(5, 5, 10), 1, 150,
+        integer_symmetric_difference:
+            symmetric_difference, integer_square(0, 0, 10), integer_square(5, 5, 10), 2, 300,
+        integer_union_apart: union, integer_square(0, 0, 1), integer_square(5, 5, 1), 2, 4,
+        integer_intersection_apart: intersection, integer_square(0, 0, 1), integer_square(5, 5, 1), 0, 0,
+        integer_difference_hole: difference, integer_square(0, 0, 10), integer_square(2, 2, 2), 1, 192,
+        integer_union_huge:
+            union, integer_square(-(1 << 60), -(1 << 60), 1 << 60), integer_square(-(1 << 59), -(1 << 59), 1 << 60),
+            1, 7 << 119,
+    );
+
+    #[test]
+    fn integer_try_out_of_range() {
+        let a = integer_square(0, 0, 10);
+        let b = integer_square(1 << 60, 0, 10);
+
+        assert!(matches!(
+            a.try_union(&b),
+            Err(Error::CoordinateOutOfRange(_))
+        ));
+        assert!(matches!(
+            MultiPolygon::from(b.clone()).try_intersection(&MultiPolygon::from(a.clone())),
+            Err(Error::CoordinateOutOfRange(_))
+        ));
+        assert_eq!(a.try_union(&a).unwrap(), a.union(&a));
+    }
+
+    #[test]
+    fn integer_crossing_points_are_rounded() {
+        // the sides cross at x = 1 + 5/6 and 3 - 5/6, which get rounded to the nearest whole number.
+        let a = integer_square(0, 0, 4);
+        let b = Polygon::new(vec![
+            Point::from_xy(1, -1),
+            Point::from_xy(2, 5),
+            Point::from_xy(3, -1),
+        ]);
+
+        let result = a.intersection(&b);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result.polygons[0].exterior.points,
+            vec![
+                Point::from_xy(1, 0),
+                Point::from_xy(2, 4),
+                Point::from_xy(3, 0),
+            ]
+        );
+
+        // the answer doesn't depend on the order of the inputs.
+        assert_eq!(
+            b.intersection(&a).polygons[0].exterior.points,
+            result.polygons[0].exterior.points
+        );
+    }
+
+    #[test]
+    fn to_integer_and_back() {
+        let poly = square(0.25, 0.5, 1.0);
+        let integer = poly.to_integer(1000.0).unwrap();
+        assert_eq!(integer.points[2], Point::from_xy(1250, 1500));
+        assert_eq!(integer.to_float(1000.0), poly);
+
+        let result = integer.union(&square(1.0, 0.5, 1.0).to_integer(1000.0).unwrap());
+        assert_eq!(result.doubled_area(), 2 * 1750 * 1000);
+        assert_f64!(result.to_float(1000.0).area(), 1.75);
+    }
+
+    #[test]
+    fn to_integer_out_of_range() {
+        assert!(matches!(
+            square(0.0, 0.0, 1.0).to_integer(1e19),
+            Err(Error::CoordinateOutOfRange(_))
+        ));
+        assert!(matches!(
+            square(0.0, 0.0, f64::NAN).to_integer(1.0),
+            Err(Error::NonFiniteCoordinate(_))
+        ));
     }
 }
@@ -0,0 +1,441 @@
+//! Polygons with holes in them
+use std::fmt;
+
+use crate::{
+    boundingbox::BoundingBox,
+    error::Error,
+    point::Point,
+    polygon::{Location, Orientation, Polygon},
+    scalar::Scalar,
+    sweep,
+};
+
+/// A polygon with an outer edge and any number of holes cut out of it.
+///
+/// The holes should be inside the exterior and should not overlap each other.
+///
+/// Like [`Polygon`] containment and intersection checks work with any [`Scalar`] coordinates, areas and
+/// transformations need `f64` ones, and `i64` coordinates have exact areas.
+#[derive(Debug, Clone)]
+pub struct PolygonWithHoles<T = f64> {
+    pub exterior: Polygon<T>,
+    pub interiors: Vec<Polygon<T>>,
+}
+
+impl<T: Scalar> PolygonWithHoles<T> {
+    pub fn new(exterior: Polygon<T>, interiors: Vec<Polygon<T>>) -> Self {
+        PolygonWithHoles {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Return the bounding box of this polygon, this is the same as the bounding box of the exterior.
+    pub fn bounds(&self) -> BoundingBox<T> {
+        self.exterior.bounds
+    }
+
+    /// Contains returns true if the point p is inside of the exterior of this polygon but not inside any of the holes.
+    ///
+    /// Points on the edges of the exterior or the holes count as inside.
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.exterior.contains(p)
+            && !self
+                .interiors
+                .iter()
+                .any(|h| h.winding_number(p).is_some_and(|w| w != 0))
+    }
+
+    /// Returns true if any part of the other polygon overlaps this one.
+    /// Entirely containing other or being contained by other counts here, sitting entirely inside a hole does not.
+    pub fn intersects(&self, other: &PolygonWithHoles<T>) -> bool {
+        if !self.exterior.intersects(&other.exterior) {
+            return false;
+        }
+
+        // the exteriors overlap, but one might be sitting inside a hole of the other.
+        !self.interiors.iter().any(|h| surrounds(h, &other.exterior))
+            && !other.interiors.iter().any(|h| surrounds(h, &self.exterior))
+    }
+
+    /// Move this polygon by point p
+    pub fn translate(&self, p: Point<T>) -> PolygonWithHoles<T> {
+        self.map_rings(|r| r.translate(p))
+    }
+
+    fn map_rings<F>(&self, f: F) -> PolygonWithHoles<T>
+    where
+        F: Fn(&Polygon<T>) -> Polygon<T>,
+    {
+        PolygonWithHoles {
+            exterior: f(&self.exterior),
+            interiors: self.interiors.iter().map(f).collect(),
+        }
+    }
+}
+
+impl PolygonWithHoles {
+    /// Group a set of rings, such as the output of the boolean operations on polygons, into polygons with holes.
+    ///
+    /// Clockwise rings are treated as exteriors and counter clockwise rings as holes.
+    /// Each hole is given to the smallest exterior that contains it, holes that are not inside any exterior are dropped.
+    /// Rings that cross themselves don't panic, but their size is just the total area their points wind around, so
+    /// holes near them might not go to the exterior you expect.
+    pub fn from_rings(rings: Vec<Polygon>) -> Vec<PolygonWithHoles> {
+        let (exteriors, holes): (Vec<Polygon>, Vec<Polygon>) = rings
+            .into_iter()
+            .partition(|r| r.orientation() == Orientation::Clockwise);
+
+        group_holes(
+            exteriors,
+            holes,
+            |exterior, hole| {
+                // the middle of an edge of a hole can't be on the edge of the exterior it belongs to
+                let (a, b) = hole.get_side(0);
+                exterior.contains(Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0))
+            },
+            |exterior| exterior.signed_area().abs(),
+        )
+    }
+
+    /// Return the area of this polygon, that is the area of the exterior minus the area of the holes.
+    pub fn area(&self) -> f64 {
+        self.exterior.area() - self.interiors.iter().map(|h| h.area()).sum::<f64>()
+    }
+
+    /// Rotate this polygon counter clockwise around the center of its exterior by angle radians
+    pub fn rotate_around_center(&self, angle: f64) -> PolygonWithHoles {
+        let center = self.exterior.center();
+        let center_inv = center.invert();
+
+        self.map_rings(|r| {
+            Polygon::new(
+                r.points
+                    .iter()
+                    .map(|p| p.translate(&center_inv).rotate(angle).translate(&center))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Rotate the entire polygon counter clockwise around the origin by angle radians
+    pub fn rotate_around_origin(&self, angle: f64) -> PolygonWithHoles {
+        self.map_rings(|r| r.rotate_around_origin(angle))
+    }
+
+    /// Scale this polygon away from the anchor point, multiplying distances along x by sx and along y by sy.
+    ///
+    /// Negative scales mirror the polygon, the exterior and holes keep going the same way around.
+    pub fn scale_around(&self, anchor: Point, sx: f64, sy: f64) -> PolygonWithHoles {
+        self.map_rings(|r| r.scale_around(anchor, sx, sy))
+    }
+
+    /// Scale this polygon away from the center of its exterior, see `scale_around`
+    pub fn scale_around_center(&self, sx: f64, sy: f64) -> PolygonWithHoles {
+        self.scale_around(self.exterior.center(), sx, sy)
+    }
+
+    /// Scale the entire polygon away from the origin, see `scale_around`
+    pub fn scale_around_origin(&self, sx: f64, sy: f64) -> PolygonWithHoles {
+        self.scale_around(Point::zero(), sx, sy)
+    }
+
+    /// Convert this polygon to i64 coordinates for the exact integer boolean operations, multiplying every coordinate
+    /// by scale and rounding it to the nearest whole number.
+    ///
+    /// Returns an error if any coordinate is not finite or ends up bigger than 2^60.
+    pub fn to_integer(&self, scale: f64) -> Result<PolygonWithHoles<i64>, Error> {
+        Ok(PolygonWithHoles {
+            exterior: self.exterior.to_integer(scale)?,
+            interiors: self
+                .interiors
+                .iter()
+                .map(|h| h.to_integer(scale))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl PolygonWithHoles<i64> {
+    /// Group a set of integer rings, such as the output of the boolean operations on integer polygons, into polygons
+    /// with holes.
+    ///
+    /// Clockwise rings are treated as exteriors and counter clockwise rings as holes. The rings must not cross
+    /// themselves or each other, but may touch at their corners.
+    /// Each hole is given to the smallest exterior that contains it, holes that are not inside any exterior are dropped.
+    pub fn from_integer_rings(rings: Vec<Polygon<i64>>) -> Vec<PolygonWithHoles<i64>> {
+        let (exteriors, holes): (Vec<Polygon<i64>>, Vec<Polygon<i64>>) =
+            rings.into_iter().partition(|r| r.doubled_signed_area() < 0);
+
+        group_holes(
+            exteriors,
+            holes,
+            |exterior, hole| {
+                // a hole can touch the exterior it belongs to at a corner, but the first corner that doesn't touch
+                // the exterior will be inside it.
+                hole.points
+                    .iter()
+                    .map(|p| exterior.locate(*p))
+                    .find(|l| !matches!(l, Location::OnBoundary(_)))
+                    == Some(Location::Inside)
+            },
+            |exterior| -exterior.doubled_signed_area(),
+        )
+    }
+
+    /// Return twice the area of this polygon, worked out exactly.
+    pub fn doubled_area(&self) -> i128 {
+        self.exterior.doubled_signed_area().abs()
+            - self
+                .interiors
+                .iter()
+                .map(|h| h.doubled_signed_area().abs())
+                .sum::<i128>()
+    }
+
+    /// Convert this polygon to f64 coordinates, dividing every coordinate by scale.
+    pub fn to_float(&self, scale: f64) -> PolygonWithHoles {
+        PolygonWithHoles {
+            exterior: self.exterior.to_float(scale),
+            interiors: self.interiors.iter().map(|h| h.to_float(scale)).collect(),
+        }
+    }
+}
+
+/// Give each hole to the smallest exterior that owns it.
+fn group_holes<T, F, S, K>(
+    exteriors: Vec<Polygon<T>>,
+    holes: Vec<Polygon<T>>,
+    owns: F,
+    size: S,
+) -> Vec<PolygonWithHoles<T>>
+where
+    T: Scalar,
+    F: Fn(&Polygon<T>, &Polygon<T>) -> bool,
+    S: Fn(&Polygon<T>) -> K,
+    K: PartialOrd,
+{
+    let mut result: Vec<PolygonWithHoles<T>> = exteriors
+        .into_iter()
+        .map(|e| PolygonWithHoles::new(e, Vec::new()))
+        .collect();
+
+    for hole in holes {
+        let owner = result
+            .iter_mut()
+            .filter(|p| owns(&p.exterior, &hole))
+            .min_by(|p, q| {
+                size(&p.exterior)
+                    .partial_cmp(&size(&q.exterior))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+        if let Some(owner) = owner {
+            owner.interiors.push(hole);
+        }
+    }
+
+    result
+}
+
+/// Is the polygon entirely inside the ring without touching its edges
+fn surrounds<T: Scalar>(ring: &Polygon<T>, polygon: &Polygon<T>) -> bool {
+    !sweep::any_intersect(&ring.sides(), &polygon.sides()) && ring.contains(polygon.points[0])
+}
+
+impl<T: Scalar> PartialEq for PolygonWithHoles<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.exterior == other.exterior && self.interiors == other.interiors
+    }
+}
+
+impl<T: Scalar> From<Polygon<T>> for PolygonWithHoles<T> {
+    fn from(exterior: Polygon<T>) -> PolygonWithHoles<T> {
+        PolygonWithHoles::new(exterior, Vec::new())
+    }
+}
+
+impl<T: Scalar> fmt::Display for PolygonWithHoles<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "PolyWithHoles({}", self.exterior)?;
+        for hole in self.interiors.iter() {
+            write!(formatter, ", {}", hole)?;
+        }
+        write!(formatter, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        point::Point,
+        polygon::Polygon,
+        tests::{assert_f64, square},
+    };
+
+    use super::PolygonWithHoles;
+
+    fn donut() -> PolygonWithHoles {
+        PolygonWithHoles::new(square(0.0, 0.0, 3.0), vec![square(1.0, 1.0, 1.0)])
+    }
+
+    macro_rules! contains_tests {
+        ($($name:ident: $test_point:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(donut().contains($test_point), $expected);
+                }
+            )*
+        };
+    }
+
+    contains_tests!(
+        in_ring: Point::new(0.5, 0.5), true,
+        in_hole: Point::new(1.5, 1.5), false,
+        outside: Point::new(4.0, 1.5), false,
+        on_hole_edge: Point::new(1.0, 1.5), true,
+    );
+
+    #[test]
+    fn area() {
+        assert_f64!(donut().area(), 8.0);
+    }
+
+    #[test]
+    fn bounds() {
+        let bounds = donut().bounds();
+        assert_eq!(bounds.a, Point::zero());
+        assert_eq!(bounds.b, Point::new(3.0, 3.0));
+    }
+
+    macro_rules! intersection_tests {
+        ($($name:ident: $other:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let other = PolygonWithHoles::from($other);
+                    assert_eq!(donut().intersects(&other), $expected);
+                    assert_eq!(other.intersects(&donut()), $expected);
+                }
+            )*
+        };
+    }
+
+    intersection_tests!(
+        inside_hole: square(1.25, 1.25, 0.5), false,
+        overlapping_hole: square(1.5, 1.5, 1.0), true,
+        covering_hole: square(0.5, 0.5, 2.0), true,
+        away: square(4.0, 4.0, 1.0), false,
+    );
+
+    #[test]
+    fn translate() {
+        let result = donut().translate(Point::new(1.0, 2.0));
+
+        let expected = PolygonWithHoles::new(square(1.0, 2.0, 3.0), vec![square(2.0, 3.0, 1.0)]);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn rotate_around_center() {
+        let result = donut().rotate_around_center(90.0_f64.to_radians());
+
+        assert_f64!(result.area(), 8.0);
+        assert!(!result.contains(Point::new(1.5, 1.5)));
+        assert!(result.contains(Point::new(0.5, 2.5)));
+    }
+
+    #[test]
+    fn rotate_around_origin() {
+        let result = donut().rotate_around_origin(90.0_f64.to_radians());
+
+        assert!(!result.contains(Point::new(-1.5, 1.5)));
+        assert!(result.contains(Point::new(-0.5, 0.5)));
+    }
+
+    #[test]
+    fn scale_around_center() {
+        let result = donut().scale_around_center(-2.0, 1.0);
+
+        assert_f64!(result.area(), 16.0);
+        assert!(!result.contains(Point::new(1.5, 1.5)));
+        assert!(result.contains(Point::new(-1.0, 1.5)));
+        assert_eq!(
+            result.exterior.orientation(),
+            donut().exterior.orientation()
+        );
+        assert_eq!(
+            result.interiors[0].orientation(),
+            donut().interiors[0].orientation()
+        );
+    }
+
+    #[test]
+    fn scale_around_origin() {
+        let result = donut().scale_around_origin(2.0, 2.0);
+
+        assert_f64!(result.area(), 32.0);
+        assert!(!result.contains(Point::new(3.0, 3.0)));
+        assert!(result.contains(Point::new(5.0, 5.0)));
+    }
+
+    #[test]
+    fn from_rings() {
+        let mut hole = square(1.0, 1.0, 1.0).points;
+        hole.reverse();
+
+        let result = PolygonWithHoles::from_rings(vec![Polygon::new(hole), square(0.0, 0.0, 3.0)]);
+
+        assert_eq!(result.len(), 1);
+        assert_f64!(result[0].area(), 8.0);
+        assert!(!result[0].contains(Point::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn from_rings_nested() {
+        // a square inside the hole of another gives two separate polygons.
+        let mut hole = square(1.0, 1.0, 3.0).points;
+        hole.reverse();
+        let rings = vec![
+            square(2.0, 2.0, 1.0),
+            square(0.0, 0.0, 5.0),
+            Polygon::new(hole),
+        ];
+
+        let result = PolygonWithHoles::from_rings(rings);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].interiors.len(), 0);
+        assert_eq!(result[1].interiors.len(), 1);
+        let total: f64 = result.iter().map(|p| p.area()).sum();
+        assert_f64!(total, 17.0);
+    }
+
+    #[test]
+    fn from_rings_self_intersecting() {
+        // the top of this ring crosses back over itself
+        let crossed = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 4.0),
+            Point::new(4.0, 4.0),
+            Point::new(4.0, 0.0),
+            Point::new(2.0, 5.0),
+        ]);
+        let mut hole = square(0.25, 3.0, 0.5).points;
+        hole.reverse();
+
+        let result = PolygonWithHoles::from_rings(vec![
+            square(-1.0, -1.0, 10.0),
+            crossed.clone(),
+            Polygon::new(hole),
+        ]);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].interiors.len(), 0);
+        assert_eq!(result[1].exterior, crossed);
+        assert_eq!(result[1].interiors.len(), 1);
+    }
+}
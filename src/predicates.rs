@@ -0,0 +1,323 @@
+//! Robust geometric predicates
+//!
+//! These follow Jonathan Shewchuk's "Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric
+//! Predicates". Each predicate is first worked out with normal floating point maths along with a bound on how wrong
+//! that could be. Only if the answer is too close to zero to trust is it worked out again exactly, by representing
+//! numbers as the sum of several floats that don't overlap (an expansion).
+//!
+//! The sign of the result is always correct. The value itself is an approximation of the determinant.
+
+// half of the machine epsilon, the relative error of a single rounding.
+const EPSILON: f64 = f64::EPSILON / 2.0;
+const SPLITTER: f64 = 134_217_729.0; // 2^27 + 1
+const CCW_ERROR_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const INCIRCLE_ERROR_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// Work out which side of the line a -> b the point c is on.
+///
+/// Returns a positive value if a, b and c go around counter clockwise, so c is to the left of the line,
+/// a negative value if they go clockwise and zero if the three points are exactly in a line.
+pub fn orient2d(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let left = (a.0 - c.0) * (b.1 - c.1);
+    let right = (a.1 - c.1) * (b.0 - c.0);
+    let det = left - right;
+
+    // if the two products have different signs there can't be any cancellation so the sign is right.
+    let sum = if left > 0.0 {
+        if right <= 0.0 {
+            return det;
+        }
+        left + right
+    } else if left < 0.0 {
+        if right >= 0.0 {
+            return det;
+        }
+        -left - right
+    } else {
+        return det;
+    };
+
+    let bound = CCW_ERROR_BOUND * sum;
+    if det >= bound || -det >= bound {
+        return det;
+    }
+
+    orient2d_exact(a, b, c)
+}
+
+fn orient2d_exact(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let acx = two_diff(a.0, c.0);
+    let acy = two_diff(a.1, c.1);
+    let bcx = two_diff(b.0, c.0);
+    let bcy = two_diff(b.1, c.1);
+
+    let det = expansion_diff(
+        &expansion_product(&acx, &bcy),
+        &expansion_product(&acy, &bcx),
+    );
+    most_significant(&det)
+}
+
+/// Work out if the point d is inside the circle through a, b and c.
+///
+/// If a, b and c go around counter clockwise this returns a positive value if d is inside the circle,
+/// a negative value if it is outside and zero if all four points are on the same circle.
+/// The sign is flipped if a, b and c go around clockwise.
+pub fn incircle(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> f64 {
+    let adx = a.0 - d.0;
+    let ady = a.1 - d.1;
+    let bdx = b.0 - d.0;
+    let bdy = b.1 - d.1;
+    let cdx = c.0 - d.0;
+    let cdy = c.1 - d.1;
+
+    let bdxcdy = bdx * cdy;
+    let cdxbdy = cdx * bdy;
+    let alift = adx * adx + ady * ady;
+
+    let cdxady = cdx * ady;
+    let adxcdy = adx * cdy;
+    let blift = bdx * bdx + bdy * bdy;
+
+    let adxbdy = adx * bdy;
+    let bdxady = bdx * ady;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    let bound = INCIRCLE_ERROR_BOUND * permanent;
+    if det > bound || -det > bound {
+        return det;
+    }
+
+    incircle_exact(a, b, c, d)
+}
+
+fn incircle_exact(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> f64 {
+    let adx = two_diff(a.0, d.0);
+    let ady = two_diff(a.1, d.1);
+    let bdx = two_diff(b.0, d.0);
+    let bdy = two_diff(b.1, d.1);
+    let cdx = two_diff(c.0, d.0);
+    let cdy = two_diff(c.1, d.1);
+
+    let lift =
+        |x: &[f64], y: &[f64]| expansion_sum(&expansion_product(x, x), &expansion_product(y, y));
+    let cross = |x1: &[f64], y1: &[f64], x2: &[f64], y2: &[f64]| {
+        expansion_diff(&expansion_product(x1, y2), &expansion_product(y1, x2))
+    };
+
+    let a_term = expansion_product(&lift(&adx, &ady), &cross(&bdx, &bdy, &cdx, &cdy));
+    let b_term = expansion_product(&lift(&bdx, &bdy), &cross(&cdx, &cdy, &adx, &ady));
+    let c_term = expansion_product(&lift(&cdx, &cdy), &cross(&adx, &ady, &bdx, &bdy));
+
+    let det = expansion_sum(&expansion_sum(&a_term, &b_term), &c_term);
+    most_significant(&det)
+}
+
+/*
+Expansion arithmetic. An expansion is a list of floats in increasing order of magnitude that don't overlap,
+whose exact sum is the number being represented.
+*/
+
+/// a + b exactly, as the rounded sum and the error in it.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let b_round = b - b_virtual;
+    let a_round = a - a_virtual;
+    (x, a_round + b_round)
+}
+
+/// a + b exactly, only valid when |a| >= |b|.
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    (x, b - b_virtual)
+}
+
+/// a - b exactly as a two part expansion.
+fn two_diff(a: f64, b: f64) -> [f64; 2] {
+    let x = a - b;
+    let b_virtual = a - x;
+    let a_virtual = x + b_virtual;
+    let b_round = b_virtual - b;
+    let a_round = a - a_virtual;
+    [a_round + b_round, x]
+}
+
+/// Split a float into two halves that can be multiplied without any rounding.
+fn split(a: f64) -> (f64, f64) {
+    let c = SPLITTER * a;
+    let a_big = c - a;
+    let high = c - a_big;
+    (high, a - high)
+}
+
+/// a * b exactly, as the rounded product and the error in it.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (a_high, a_low) = split(a);
+    let (b_high, b_low) = split(b);
+    let err1 = x - (a_high * b_high);
+    let err2 = err1 - (a_low * b_high);
+    let err3 = err2 - (a_high * b_low);
+    (x, (a_low * b_low) - err3)
+}
+
+/// Add a single float to an expansion, dropping any zero parts.
+fn grow_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for component in e {
+        let (sum, err) = two_sum(q, *component);
+        if err != 0.0 {
+            result.push(err);
+        }
+        q = sum;
+    }
+    if q != 0.0 || result.is_empty() {
+        result.push(q);
+    }
+    result
+}
+
+fn expansion_sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |sum, component| {
+        grow_expansion(&sum, *component)
+    })
+}
+
+fn expansion_diff(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let negated: Vec<f64> = f.iter().map(|c| -c).collect();
+    expansion_sum(e, &negated)
+}
+
+/// Multiply an expansion by a single float, dropping any zero parts.
+fn scale_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(e.len() * 2);
+    let (mut q, err) = two_product(e[0], b);
+    if err != 0.0 {
+        result.push(err);
+    }
+    for component in &e[1..] {
+        let (product_high, product_low) = two_product(*component, b);
+        let (sum, err) = two_sum(q, product_low);
+        if err != 0.0 {
+            result.push(err);
+        }
+        let (new_q, err) = fast_two_sum(product_high, sum);
+        if err != 0.0 {
+            result.push(err);
+        }
+        q = new_q;
+    }
+    if q != 0.0 || result.is_empty() {
+        result.push(q);
+    }
+    result
+}
+
+fn expansion_product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(vec![0.0], |sum, component| {
+        expansion_sum(&sum, &scale_expansion(e, *component))
+    })
+}
+
+/// The largest part of an expansion, which has the same sign as the whole thing.
+fn most_significant(e: &[f64]) -> f64 {
+    e.last().copied().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{incircle, orient2d};
+
+    // the gap between floats just above 0.5
+    const ULP: f64 = f64::EPSILON / 2.0;
+
+    #[test]
+    fn orient2d_simple() {
+        assert!(orient2d((0.0, 0.0), (1.0, 0.0), (0.0, 1.0)) > 0.0);
+        assert!(orient2d((0.0, 0.0), (0.0, 1.0), (1.0, 0.0)) < 0.0);
+        assert_eq!(orient2d((0.0, 0.0), (1.0, 1.0), (2.0, 2.0)), 0.0);
+    }
+
+    #[test]
+    fn orient2d_nearly_collinear() {
+        // points a tiny bit off the line y = x, the true answer has the same sign as ay - ax.
+        let b = (12.0, 12.0);
+        let c = (24.0, 24.0);
+
+        let mut naive_wrong = 0;
+        for i in 0..64_i32 {
+            for j in 0..64_i32 {
+                let a = (0.5 + i as f64 * ULP, 0.5 + j as f64 * ULP);
+                let expected = (j - i).signum();
+
+                let result = orient2d(a, b, c);
+                assert_eq!(
+                    if result == 0.0 {
+                        0
+                    } else {
+                        result.signum() as i32
+                    },
+                    expected,
+                    "wrong sign for {:?}",
+                    a
+                );
+
+                let naive = (a.0 - c.0) * (b.1 - c.1) - (a.1 - c.1) * (b.0 - c.0);
+                let naive_sign = if naive == 0.0 {
+                    0
+                } else {
+                    naive.signum() as i32
+                };
+                if naive_sign != expected {
+                    naive_wrong += 1;
+                }
+            }
+        }
+
+        // make sure this is actually a hard case for plain floating point maths.
+        assert!(naive_wrong > 0);
+    }
+
+    #[test]
+    fn incircle_simple() {
+        let a = (1.0, 0.0);
+        let b = (0.0, 1.0);
+        let c = (-1.0, 0.0);
+
+        assert!(incircle(a, b, c, (0.0, 0.0)) > 0.0);
+        assert!(incircle(a, b, c, (2.0, 0.0)) < 0.0);
+        assert_eq!(incircle(a, b, c, (0.0, -1.0)), 0.0);
+        // clockwise flips the sign
+        assert!(incircle(c, b, a, (0.0, 0.0)) < 0.0);
+    }
+
+    #[test]
+    fn incircle_far_from_origin() {
+        let center = (1e9, 1e9);
+        let offset = |x: f64, y: f64| (center.0 + x, center.1 + y);
+
+        let a = offset(5.0, 0.0);
+        let b = offset(3.0, 4.0);
+        let c = offset(-3.0, 4.0);
+
+        assert_eq!(incircle(a, b, c, offset(0.0, -5.0)), 0.0);
+
+        // move the last point the smallest possible amount in or out of the circle
+        let on = offset(-4.0, -3.0);
+        let inside = (on.0 + 1e9 * f64::EPSILON, on.1 + 1e9 * f64::EPSILON);
+        let outside = (on.0 - 1e9 * f64::EPSILON, on.1 - 1e9 * f64::EPSILON);
+
+        assert_eq!(incircle(a, b, c, on), 0.0);
+        assert!(incircle(a, b, c, inside) > 0.0);
+        assert!(incircle(a, b, c, outside) < 0.0);
+    }
+}
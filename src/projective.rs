@@ -0,0 +1,275 @@
+//! Projective transforms (homographies), which can map any four sided shape onto any other
+use std::fmt;
+use std::ops::Mul;
+
+use crate::{
+    affine::{Affine2, Transformable},
+    point::Point,
+};
+
+/// A 2d projective transform, stored as a 3x3 matrix.
+///
+/// Unlike an [`Affine2`] this can change parallel lines so they meet, like looking at a floor through a camera.
+/// Straight lines always stay straight.
+///
+/// ```
+/// use polygonical::point::Point;
+/// use polygonical::projective::Projective;
+///
+/// // the corners of a square seen at an angle, and where they are on the floor plan
+/// let image = [(0.0, 0.0), (4.0, 0.0), (3.0, 2.0), (1.0, 2.0)].map(Point::from);
+/// let floor = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(Point::from);
+///
+/// let transform = Projective::from_points(image, floor).unwrap();
+/// assert_eq!(transform.apply(&Point::new(3.0, 2.0)), Point::new(1.0, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Projective {
+    pub matrix: [[f64; 3]; 3],
+}
+
+impl Projective {
+    pub const fn new(matrix: [[f64; 3]; 3]) -> Self {
+        Projective { matrix }
+    }
+
+    /// The transform that leaves everything where it is.
+    pub const fn identity() -> Self {
+        Projective::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Create the transform that moves each of the from points to the matching to point.
+    ///
+    /// Returns None if three of the points on either side are in a line, as then there is no such transform.
+    pub fn from_points(from: [Point; 4], to: [Point; 4]) -> Option<Projective> {
+        let from = basis_to_points(from)?;
+        let to = basis_to_points(to)?;
+
+        Some(to * from.invert()?)
+    }
+
+    /// Create a transform that does this one and then the next one.
+    pub fn then(&self, next: &Projective) -> Projective {
+        *next * *self
+    }
+
+    /// Create the transform that undoes this one.
+    /// Returns None if this squashes everything onto a line or a point, as that can't be undone.
+    pub fn invert(&self) -> Option<Projective> {
+        let m = self.matrix;
+        let minor = |r1: usize, r2: usize, c1: usize, c2: usize| {
+            m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+        };
+
+        let det =
+            m[0][0] * minor(1, 2, 1, 2) - m[0][1] * minor(1, 2, 0, 2) + m[0][2] * minor(1, 2, 0, 1);
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        // the adjugate divided by the determinant
+        let inverse = [
+            [
+                minor(1, 2, 1, 2) / det,
+                -minor(0, 2, 1, 2) / det,
+                minor(0, 1, 1, 2) / det,
+            ],
+            [
+                -minor(1, 2, 0, 2) / det,
+                minor(0, 2, 0, 2) / det,
+                -minor(0, 1, 0, 2) / det,
+            ],
+            [
+                minor(1, 2, 0, 1) / det,
+                -minor(0, 2, 0, 1) / det,
+                minor(0, 1, 0, 1) / det,
+            ],
+        ];
+        Some(Projective::new(inverse))
+    }
+
+    /// Move a single point.
+    ///
+    /// Points on the line that this transform sends off to infinity come back with infinite or NaN coordinates.
+    pub fn transform_point(&self, p: Point) -> Point {
+        let m = self.matrix;
+        let w = m[2][0] * p.x + m[2][1] * p.y + m[2][2];
+        Point::new(
+            (m[0][0] * p.x + m[0][1] * p.y + m[0][2]) / w,
+            (m[1][0] * p.x + m[1][1] * p.y + m[1][2]) / w,
+        )
+    }
+
+    /// Create a copy of the shape with this transform applied to it.
+    pub fn apply<S: Transformable>(&self, shape: &S) -> S {
+        shape.map_points(|p| self.transform_point(p))
+    }
+}
+
+/// The matrix that moves the points at infinity along x and along y, the origin and (1, 1) to the given points.
+fn basis_to_points(points: [Point; 4]) -> Option<Projective> {
+    let [p0, p1, p2, p3] = points;
+    let m = Projective::new([[p0.x, p1.x, p2.x], [p0.y, p1.y, p2.y], [1.0, 1.0, 1.0]]);
+
+    // the weights that make the fourth point the sum of the other three
+    let inverse = m.invert()?.matrix;
+    let weight = |row: usize| inverse[row][0] * p3.x + inverse[row][1] * p3.y + inverse[row][2];
+    let weights = [weight(0), weight(1), weight(2)];
+    if weights.contains(&0.0) {
+        return None;
+    }
+
+    Some(Projective::new(
+        m.matrix.map(|row| [0, 1, 2].map(|c| row[c] * weights[c])),
+    ))
+}
+
+impl Default for Projective {
+    fn default() -> Self {
+        Projective::identity()
+    }
+}
+
+impl From<Affine2> for Projective {
+    fn from(affine: Affine2) -> Self {
+        Projective::new([
+            [affine.a, affine.b, affine.c],
+            [affine.d, affine.e, affine.f],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+/// Matrix multiplication, `x * y` does y first and then x.
+impl Mul for Projective {
+    type Output = Projective;
+
+    fn mul(self, other: Projective) -> Projective {
+        let (a, b) = (self.matrix, other.matrix);
+        Projective::new(
+            [0, 1, 2].map(|r| {
+                [0, 1, 2].map(|c| a[r][0] * b[0][c] + a[r][1] * b[1][c] + a[r][2] * b[2][c])
+            }),
+        )
+    }
+}
+
+impl fmt::Display for Projective {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = self.matrix;
+        write!(
+            formatter,
+            "Projective([{}, {}, {}], [{}, {}, {}], [{}, {}, {}])",
+            m[0][0], m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], m[2][0], m[2][1], m[2][2]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Projective;
+    use crate::{affine::Affine2, point::Point, polygon::Polygon, tests::assert_f64};
+
+    fn unit_square() -> [Point; 4] {
+        [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(Point::from)
+    }
+
+    fn trapezoid() -> [Point; 4] {
+        [(0.0, 0.0), (4.0, 0.0), (3.0, 2.0), (1.0, 2.0)].map(Point::from)
+    }
+
+    macro_rules! from_points_tests {
+        ($($name:ident: $from:expr, $to:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (from, to) = ($from, $to);
+                    let transform = Projective::from_points(from, to).unwrap();
+                    let inverse = transform.invert().unwrap();
+
+                    for (f, t) in from.iter().zip(to.iter()) {
+                        assert_eq!(transform.apply(f), *t);
+                        assert_eq!(inverse.apply(t), *f);
+                    }
+                }
+            )*
+        };
+    }
+
+    from_points_tests!(
+        square_to_trapezoid: unit_square(), trapezoid(),
+        trapezoid_to_square: trapezoid(), unit_square(),
+        square_to_itself: unit_square(), unit_square(),
+        square_to_rotated_square: unit_square(), unit_square().map(|p| p.rotate(0.3)),
+        square_to_mirrored: unit_square(), unit_square().map(|p| p.scale(-1.0, 1.0)),
+        camera_image: [(102.0, 511.0), (1750.0, 488.0), (1322.0, 83.0), (431.0, 97.0)].map(Point::from),
+            [(0.0, 0.0), (12.5, 0.0), (12.5, 30.0), (0.0, 30.0)].map(Point::from),
+    );
+
+    #[test]
+    fn keeps_straight_lines() {
+        // the middle of the square is where its diagonals cross, so it ends up where the trapezoid's diagonals cross
+        let transform = Projective::from_points(unit_square(), trapezoid()).unwrap();
+        let center = transform.apply(&Point::new(0.5, 0.5));
+
+        assert_f64!(center.x, 2.0);
+        assert_f64!(center.y, 4.0 / 3.0);
+    }
+
+    #[test]
+    fn polygon_round_trip() {
+        let transform = Projective::from_points(trapezoid(), unit_square()).unwrap();
+        let inverse = transform.invert().unwrap();
+
+        let poly = Polygon::new(vec![
+            Point::new(1.0, 0.5),
+            Point::new(2.0, 1.5),
+            Point::new(3.0, 0.5),
+            Point::new(2.0, 1.0),
+        ]);
+
+        let there = transform.apply(&poly);
+        assert!(there.points.iter().all(|p| p.x > 0.0 && p.x < 1.0));
+        assert_eq!(inverse.apply(&there), poly);
+        assert_eq!(transform.then(&inverse).apply(&poly), poly);
+    }
+
+    #[test]
+    fn polygon_mirrored() {
+        let from = unit_square();
+        let transform = Projective::from_points(from, from.map(|p| p.scale(1.0, -1.0))).unwrap();
+        let poly = Polygon::new(from.to_vec());
+
+        let result = transform.apply(&poly);
+        assert_eq!(result.orientation(), poly.orientation());
+        assert_f64!(result.area(), 1.0);
+        assert!(result.contains(Point::new(0.5, -0.5)));
+    }
+
+    #[test]
+    fn from_affine() {
+        let affine = Affine2::rotate(0.8)
+            .then(&Affine2::shear(0.5, 0.0))
+            .then(&Affine2::translate(Point::new(2.0, -1.0)));
+        let transform = Projective::from(affine);
+        let p = Point::new(3.0, 7.0);
+
+        assert_eq!(transform.apply(&p), affine.apply(&p));
+
+        let corners = unit_square();
+        let from_points =
+            Projective::from_points(corners, corners.map(|c| affine.apply(&c))).unwrap();
+        assert_eq!(from_points.apply(&p), affine.apply(&p));
+    }
+
+    #[test]
+    fn degenerate() {
+        let in_a_line = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (0.0, 1.0)].map(Point::from);
+        assert!(Projective::from_points(unit_square(), in_a_line).is_none());
+        assert!(Projective::from_points(in_a_line, unit_square()).is_none());
+
+        let last_in_a_line = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (2.0, 0.0)].map(Point::from);
+        assert!(Projective::from_points(last_in_a_line, unit_square()).is_none());
+    }
+}
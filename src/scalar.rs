@@ -0,0 +1,263 @@
+//! The number types that can be used for coordinates
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    ops::{Add, Neg, Sub},
+};
+
+use float_cmp::approx_eq;
+
+use crate::{predicates, tolerance::Tolerance};
+
+/// A number type that can be used for the coordinates of points.
+///
+/// This is implemented for `f32`, `f64`, `i32` and `i64`. The orientation test is exact for all of them, so
+/// intersection and containment checks always give the right answer. For integers it is worked out with 128 bit
+/// maths, which gets the sign right for any coordinates the type can hold.
+///
+/// Things that need to produce new coordinates, like areas and rotations, are only available for `f64`. The boolean
+/// operations are available for `f64`, and for `i64` where they are worked out exactly apart from rounding the points
+/// where edges cross. Use `Point::cast` and `Polygon::cast` to convert between coordinate types.
+pub trait Scalar:
+    Copy + PartialOrd + Debug + Display + Add<Output = Self> + Sub<Output = Self> + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    /// Convert to a f64. Integers bigger than 2^53 will lose precision.
+    fn to_f64(self) -> f64;
+
+    /// Convert from a f64. Integers are rounded to the nearest value and saturate at their min and max.
+    fn from_f64(value: f64) -> Self;
+
+    fn is_finite(self) -> bool;
+
+    fn is_nan(self) -> bool;
+
+    /// Order two values, putting NaNs at the ends rather than failing like `partial_cmp` does.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// Returns true if the two values are close enough to count as the same. Integers must be exactly equal.
+    fn approx_eq(self, other: Self, tolerance: Tolerance) -> bool;
+
+    /// Work out which side of the line a -> b the point c is on, see [`predicates::orient2d`].
+    ///
+    /// Positive if c is to the left of the line, negative if it is to the right and zero if they are exactly in a line.
+    /// The sign is always exact, the size is twice the area of the triangle rounded to the nearest f64.
+    fn orient2d(a: (Self, Self), b: (Self, Self), c: (Self, Self)) -> f64;
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const MIN: Self = f64::MIN;
+    const MAX: Self = f64::MAX;
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+
+    fn approx_eq(self, other: Self, tolerance: Tolerance) -> bool {
+        tolerance.approx_eq(self, other)
+    }
+
+    fn orient2d(a: (Self, Self), b: (Self, Self), c: (Self, Self)) -> f64 {
+        predicates::orient2d(a, b, c)
+    }
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const MIN: Self = f32::MIN;
+    const MAX: Self = f32::MAX;
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+
+    fn approx_eq(self, other: Self, tolerance: Tolerance) -> bool {
+        approx_eq!(
+            f32,
+            self,
+            other,
+            epsilon = tolerance.epsilon as f32,
+            ulps = tolerance.ulps as i32
+        )
+    }
+
+    fn orient2d(a: (Self, Self), b: (Self, Self), c: (Self, Self)) -> f64 {
+        // every f32 is exactly representable as a f64 so this loses nothing.
+        predicates::orient2d(
+            (a.0 as f64, a.1 as f64),
+            (b.0 as f64, b.1 as f64),
+            (c.0 as f64, c.1 as f64),
+        )
+    }
+}
+
+macro_rules! integer_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $t
+                }
+
+                fn is_finite(self) -> bool {
+                    true
+                }
+
+                fn is_nan(self) -> bool {
+                    false
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+
+                fn approx_eq(self, other: Self, _tolerance: Tolerance) -> bool {
+                    self == other
+                }
+
+                fn orient2d(a: (Self, Self), b: (Self, Self), c: (Self, Self)) -> f64 {
+                    integer_orient2d(
+                        (a.0 as i128, a.1 as i128),
+                        (b.0 as i128, b.1 as i128),
+                        (c.0 as i128, c.1 as i128),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+integer_scalar!(i32, i64);
+
+/// The orientation test for integer coordinates, with the sign right for any i64 coordinates.
+///
+/// The differences need 65 bits, so the two products can need 128 bits before their sign and would overflow an i128.
+/// Their sizes and signs are worked out separately instead. The size of the answer is rounded to the nearest f64.
+fn integer_orient2d(a: (i128, i128), b: (i128, i128), c: (i128, i128)) -> f64 {
+    let product = |x: i128, y: i128| {
+        let negative = x != 0 && y != 0 && (x < 0) != (y < 0);
+        (negative, x.unsigned_abs() * y.unsigned_abs())
+    };
+    let (left_negative, left) = product(a.0 - c.0, b.1 - c.1);
+    let (right_negative, right) = product(a.1 - c.1, b.0 - c.0);
+
+    // left - right
+    match (left_negative, right_negative) {
+        (false, true) => left as f64 + right as f64,
+        (true, false) => -(left as f64 + right as f64),
+        (negative, _) => {
+            let difference = if left >= right {
+                (left - right) as f64
+            } else {
+                -((right - left) as f64)
+            };
+            if negative {
+                -difference
+            } else {
+                difference
+            }
+        }
+    }
+}
+
+/// The smaller of a and b. Like `f64::min` if one of them is NaN the other is returned.
+pub(crate) fn min<T: Scalar>(a: T, b: T) -> T {
+    if a.is_nan() || b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// The larger of a and b. Like `f64::max` if one of them is NaN the other is returned.
+pub(crate) fn max<T: Scalar>(a: T, b: T) -> T {
+    if a.is_nan() || b > a {
+        b
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scalar;
+
+    macro_rules! orient2d_tests {
+        ($($name:ident: $t:ty, $a:expr, $b:expr, $c:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = <$t>::orient2d($a, $b, $c);
+                    let sign = if result == 0.0 { 0 } else { result.signum() as i32 };
+                    assert_eq!(sign, $expected);
+                }
+            )*
+        };
+    }
+
+    orient2d_tests!(
+        f32_left: f32, (0.0, 0.0), (1.0, 0.0), (0.0, 1.0), 1,
+        f32_right: f32, (0.0, 0.0), (0.0, 1.0), (1.0, 0.0), -1,
+        i32_left: i32, (0, 0), (1, 0), (0, 1), 1,
+        i32_extremes: i32, (i32::MIN, i32::MIN), (i32::MAX, i32::MAX), (i32::MIN, i32::MAX), 1,
+        i64_collinear: i64, (0, 0), (1 << 40, 1 << 40), (1 << 41, 1 << 41), 0,
+        // the products here are around 2^122, far past what an i64 or f64 can hold exactly.
+        i64_nearly_collinear: i64, ((1 << 61) + 1, 1 << 61), (1 << 61, (1 << 61) - 1), (0, 0), -1,
+        // the differences and products here don't fit in an i64 or an i128.
+        i64_extremes: i64, (i64::MIN, i64::MIN), (i64::MAX, i64::MAX), (i64::MIN, i64::MAX), 1,
+        i64_extremes_collinear: i64, (i64::MIN, i64::MIN), (i64::MAX, i64::MAX), (i64::MAX - 1, i64::MAX - 1), 0,
+        i64_extremes_nearly_collinear: i64, (i64::MIN, i64::MIN), (i64::MAX, i64::MAX), (i64::MAX, i64::MAX - 1), -1,
+        i64_extremes_mixed_signs: i64, (i64::MIN, i64::MAX), (i64::MAX, i64::MIN), (i64::MAX, i64::MAX), 1,
+    );
+
+    #[test]
+    fn integer_from_f64() {
+        assert_eq!(i64::from_f64(2.6), 3);
+        assert_eq!(i32::from_f64(-2.6), -3);
+        assert_eq!(i32::from_f64(1e20), i32::MAX);
+    }
+}
@@ -0,0 +1,300 @@
+//! Sweep line search for intersecting line segments. None of this is exposed outside the library
+//!
+//! Segments are sorted by their smallest x coordinate and a line is swept across them from left to right. The y ranges
+//! of the segments the sweep line is currently crossing are kept in an interval tree, so each new segment is only
+//! compared against the active segments whose y range overlaps its own. That makes the search
+//! O(n log² n + k) where k is the number of pairs of segments with overlapping bounding boxes, rather than comparing
+//! every pair of edges. It is not output sensitive in the number of intersections like Bentley–Ottmann, long edges
+//! that overlap lots of others in both x and y are still compared with all of them.
+//!
+//! Unlike a full Bentley–Ottmann status structure nothing here depends on computed intersection points, every
+//! candidate pair is checked with the same test as `geom::lines_intersect`, so no intersections can be missed due to
+//! floating point rounding.
+use std::collections::BTreeSet;
+use std::ops::ControlFlow;
+
+use crate::{
+    geom,
+    point::Point,
+    scalar::{self, Scalar},
+};
+
+/// Call found with the indexes of every pair of segments that intersect, including segments that only touch at
+/// their ends and segments that overlap each other. The first index is always the smaller.
+///
+/// Returning `ControlFlow::Break` from found stops the search early, and is passed back to the caller.
+pub fn intersecting_pairs<T, F>(segments: &[(Point<T>, Point<T>)], mut found: F) -> ControlFlow<()>
+where
+    T: Scalar,
+    F: FnMut(usize, usize) -> ControlFlow<()>,
+{
+    let mut by_start: Vec<usize> = (0..segments.len()).collect();
+    by_start.sort_by(|a, b| min_x(&segments[*a]).total_cmp(&min_x(&segments[*b])));
+    let mut by_end: Vec<usize> = (0..segments.len()).collect();
+    by_end.sort_by(|a, b| max_x(&segments[*a]).total_cmp(&max_x(&segments[*b])));
+
+    let mut active = ActiveRanges::new(segments);
+    let mut finished = 0;
+    let mut candidates = Vec::new();
+    for i in by_start {
+        let (a, b) = segments[i];
+        let start = min_x(&segments[i]);
+
+        // anything that finishes before this segment starts can't touch this or anything after it.
+        while finished < by_end.len() && max_x(&segments[by_end[finished]]) < start {
+            active.remove(by_end[finished]);
+            finished += 1;
+        }
+
+        candidates.clear();
+        active.overlapping(i, &mut candidates);
+        for j in candidates.iter() {
+            let (c, d) = segments[*j];
+            if geom::lines_intersect(a, b, c, d) {
+                found(i.min(*j), i.max(*j))?;
+            }
+        }
+
+        active.insert(i);
+    }
+
+    ControlFlow::Continue(())
+}
+
+/// Do any of the segments in a intersect any of the segments in b.
+pub fn any_intersect<T: Scalar>(a: &[(Point<T>, Point<T>)], b: &[(Point<T>, Point<T>)]) -> bool {
+    let segments: Vec<(Point<T>, Point<T>)> = a.iter().chain(b.iter()).copied().collect();
+    intersecting_pairs(&segments, |i, j| {
+        // the indexes of b's segments come after a's.
+        if i < a.len() && j >= a.len() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_break()
+}
+
+/// The y ranges of the segments the sweep line is crossing.
+///
+/// Each range is stored as the positions of its ends in the sorted list of all the y coordinates, with equal
+/// coordinates at the same position. The active ranges that overlap a new one either start inside it, found in order of
+/// where they start, or start below it and reach its bottom, found by walking down a segment tree over the positions.
+struct ActiveRanges {
+    ranges: Vec<(usize, usize)>,
+    by_low: BTreeSet<(usize, usize)>,
+    // each range is stored at the nodes of the tree that between them exactly cover it
+    tree: Vec<BTreeSet<usize>>,
+    positions: usize,
+}
+
+impl ActiveRanges {
+    fn new<T: Scalar>(segments: &[(Point<T>, Point<T>)]) -> Self {
+        let mut ends: Vec<(T, usize, bool)> = segments
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (a, b))| {
+                [
+                    (scalar::min(a.y, b.y), i, false),
+                    (scalar::max(a.y, b.y), i, true),
+                ]
+            })
+            .collect();
+        ends.sort_by(|p, q| p.0.total_cmp(&q.0));
+
+        let mut ranges = vec![(0, 0); segments.len()];
+        let mut position = 0;
+        for (k, (y, i, high)) in ends.iter().enumerate() {
+            if k > 0 && ends[k - 1].0 != *y {
+                position += 1;
+            }
+            if *high {
+                ranges[*i].1 = position;
+            } else {
+                ranges[*i].0 = position;
+            }
+        }
+
+        let positions = position + 1;
+        ActiveRanges {
+            ranges,
+            by_low: BTreeSet::new(),
+            tree: vec![BTreeSet::new(); 4 * positions],
+            positions,
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.by_low.insert((self.ranges[i].0, i));
+        self.update(1, 0, self.positions - 1, i, true);
+    }
+
+    fn remove(&mut self, i: usize) {
+        self.by_low.remove(&(self.ranges[i].0, i));
+        self.update(1, 0, self.positions - 1, i, false);
+    }
+
+    fn update(&mut self, node: usize, from: usize, to: usize, i: usize, insert: bool) {
+        let (low, high) = self.ranges[i];
+        if high < from || low > to {
+            return;
+        }
+
+        if low <= from && to <= high {
+            if insert {
+                self.tree[node].insert(i);
+            } else {
+                self.tree[node].remove(&i);
+            }
+            return;
+        }
+
+        let middle = (from + to) / 2;
+        self.update(2 * node, from, middle, i, insert);
+        self.update(2 * node + 1, middle + 1, to, i, insert);
+    }
+
+    /// Add every active range that overlaps the range of segment i to result.
+    fn overlapping(&self, i: usize, result: &mut Vec<usize>) {
+        let (low, high) = self.ranges[i];
+        result.extend(
+            self.by_low
+                .range((low, 0)..=(high, usize::MAX))
+                .map(|(_, j)| *j),
+        );
+
+        // the ranges on the path down to low all contain it, the ones starting at low were found above.
+        let (mut node, mut from, mut to) = (1, 0, self.positions - 1);
+        loop {
+            result.extend(self.tree[node].iter().filter(|j| self.ranges[**j].0 < low));
+            if from == to {
+                break;
+            }
+
+            let middle = (from + to) / 2;
+            if low <= middle {
+                (node, to) = (2 * node, middle);
+            } else {
+                (node, from) = (2 * node + 1, middle + 1);
+            }
+        }
+    }
+}
+
+fn min_x<T: Scalar>(segment: &(Point<T>, Point<T>)) -> T {
+    scalar::min(segment.0.x, segment.1.x)
+}
+
+fn max_x<T: Scalar>(segment: &(Point<T>, Point<T>)) -> T {
+    scalar::max(segment.0.x, segment.1.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use super::intersecting_pairs;
+    use crate::{geom, point::Point};
+
+    fn all_pairs(segments: &[(Point, Point)]) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        let _ = intersecting_pairs(segments, |i, j| {
+            result.push((i, j));
+            ControlFlow::Continue(())
+        });
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn finds_crossing_touching_and_overlapping() {
+        let segments = vec![
+            (Point::new(0.0, 0.0), Point::new(2.0, 2.0)),
+            (Point::new(0.0, 2.0), Point::new(2.0, 0.0)),
+            (Point::new(2.0, 2.0), Point::new(3.0, 2.0)),
+            (Point::new(2.5, 2.0), Point::new(4.0, 2.0)),
+            (Point::new(5.0, 0.0), Point::new(5.0, 5.0)),
+        ];
+
+        assert_eq!(all_pairs(&segments), vec![(0, 1), (0, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn vertical_segments() {
+        let segments = vec![
+            (Point::new(1.0, 0.0), Point::new(1.0, 3.0)),
+            (Point::new(0.0, 1.0), Point::new(1.0, 1.0)),
+            (Point::new(1.0, 2.0), Point::new(1.0, 5.0)),
+            (Point::new(0.5, 4.0), Point::new(0.5, 6.0)),
+        ];
+
+        assert_eq!(all_pairs(&segments), vec![(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn stops_early() {
+        let segments = vec![
+            (Point::new(0.0, 0.0), Point::new(2.0, 2.0)),
+            (Point::new(0.0, 2.0), Point::new(2.0, 0.0)),
+            (Point::new(1.0, 0.0), Point::new(1.0, 2.0)),
+        ];
+
+        let mut count = 0;
+        let result = intersecting_pairs(&segments, |_, _| {
+            count += 1;
+            ControlFlow::Break(())
+        });
+
+        assert_eq!(result, ControlFlow::Break(()));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn matches_every_pair() {
+        // a jagged star shape has lots of crossing edges at all sorts of angles.
+        let points: Vec<Point> = (0..40)
+            .map(|i| {
+                let radius = if i % 2 == 0 {
+                    10.0
+                } else {
+                    3.0 + (i % 7) as f64
+                };
+                Point::new(radius, 0.0).rotate((i as f64 * 97.0).to_radians())
+            })
+            .collect();
+        let segments: Vec<(Point, Point)> = (0..points.len())
+            .map(|i| (points[i], points[(i + 1) % points.len()]))
+            .collect();
+
+        let mut expected = Vec::new();
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                let (a, b) = segments[i];
+                let (c, d) = segments[j];
+                if geom::lines_intersect(a, b, c, d) {
+                    expected.push((i, j));
+                }
+            }
+        }
+
+        assert!(expected.len() > segments.len());
+        assert_eq!(all_pairs(&segments), expected);
+    }
+
+    #[test]
+    fn large_polygon() {
+        // every side of a circle only touches its two neighbours. Comparing every pair would take far too long here.
+        let n = 100_000;
+        let points: Vec<Point> = (0..n)
+            .map(|i| Point::new(1000.0, 0.0).rotate(i as f64 * std::f64::consts::TAU / n as f64))
+            .collect();
+        let segments: Vec<(Point, Point)> =
+            (0..n).map(|i| (points[i], points[(i + 1) % n])).collect();
+
+        let pairs = all_pairs(&segments);
+        assert_eq!(pairs.len(), n);
+        assert!(pairs
+            .iter()
+            .all(|(i, j)| j - i == 1 || (*i, *j) == (0, n - 1)));
+    }
+}
@@ -0,0 +1,82 @@
+//! Control over how close coordinates have to be to count as the same
+use float_cmp::approx_eq;
+
+/// How close two coordinates have to be to count as the same.
+///
+/// Two values are equal if they are within `epsilon` of each other or within `ulps` representable floats of each
+/// other. The epsilon should suit the scale of your coordinates, data in millimetres needs a much smaller one than
+/// data in metres or degrees.
+///
+/// The default, an epsilon of 0.000003 and 2 ulps, is what `Point` and `Polygon` use for `==`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub epsilon: f64,
+    pub ulps: i64,
+}
+
+impl Tolerance {
+    pub const fn new(epsilon: f64, ulps: i64) -> Self {
+        Tolerance { epsilon, ulps }
+    }
+
+    /// A tolerance where values have to be exactly the same to be equal, and snapping does nothing.
+    pub const fn exact() -> Self {
+        Tolerance::new(0.0, 0)
+    }
+
+    /// Returns true if a and b are close enough to count as the same.
+    pub fn approx_eq(&self, a: f64, b: f64) -> bool {
+        approx_eq!(f64, a, b, epsilon = self.epsilon, ulps = self.ulps)
+    }
+
+    /// Round a value to the nearest multiple of epsilon. If epsilon is zero the value is returned unchanged.
+    pub fn snap(&self, value: f64) -> f64 {
+        if self.epsilon == 0.0 {
+            return value;
+        }
+        (value / self.epsilon).round() * self.epsilon
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::new(0.000003, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tolerance;
+    use crate::tests::assert_f64;
+
+    macro_rules! approx_eq_tests {
+        ($($name:ident: $tolerance:expr, $a:expr, $b:expr, $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($tolerance.approx_eq($a, $b), $expected);
+                }
+            )*
+        };
+    }
+
+    approx_eq_tests!(
+        default_close: Tolerance::default(), 1.0, 1.000001, true,
+        default_far: Tolerance::default(), 1.0, 1.0001, false,
+        millimetres_close: Tolerance::new(1e-9, 2), 1.0, 1.0 + 1e-10, true,
+        millimetres_far: Tolerance::new(1e-9, 2), 1.0, 1.000001, false,
+        metres_close: Tolerance::new(0.01, 2), 500_000.0, 500_000.005, true,
+        exact_same: Tolerance::exact(), 0.1, 0.1, true,
+        exact_next_float: Tolerance::exact(), 1.0, 1.0 + f64::EPSILON, false,
+    );
+
+    #[test]
+    fn snap() {
+        let tolerance = Tolerance::new(0.5, 0);
+        assert_f64!(tolerance.snap(1.2), 1.0);
+        assert_f64!(tolerance.snap(1.3), 1.5);
+        assert_f64!(tolerance.snap(-0.7), -0.5);
+
+        assert_eq!(Tolerance::exact().snap(0.123456789), 0.123456789);
+    }
+}